@@ -0,0 +1,602 @@
+//! Solvers that work through a finished maze one step at a time, the way a person might, so the
+//! viewer can show them moving. Each one implements [`SolverAgent`].
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::generators::{has_wall_between, maze_neighbors, Highlight};
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, GridCellKind};
+use crate::solve;
+
+/// The names of every available agent, in the order the viewer cycles through them.
+pub const AGENT_NAMES: &[&str] = &[
+    "left-hand",
+    "right-hand",
+    "tremaux",
+    "dead-end-filling",
+    "random-mouse",
+];
+
+/// Creates the agent with the given name, starting at the maze's entrance. Returns `None` if the
+/// name isn't one of [`AGENT_NAMES`] or the maze has no entrance and exit.
+pub fn create_agent(name: &str, grid: &CellGrid) -> Option<Box<dyn SolverAgent>> {
+    let (start, end) = solve::find_entrance_and_exit(grid)?;
+    match name {
+        "left-hand" => Some(Box::new(WallFollower::new(grid, start, end, Hand::Left))),
+        "right-hand" => Some(Box::new(WallFollower::new(grid, start, end, Hand::Right))),
+        "tremaux" => Some(Box::new(TremauxAgent::new(grid, start, end))),
+        "dead-end-filling" => Some(Box::new(DeadEndFiller::new(grid, start, end))),
+        "random-mouse" => Some(Box::new(RandomMouse::new(grid, start, end))),
+        _ => None,
+    }
+}
+
+/// How an agent is doing.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AgentStatus {
+    /// Still going.
+    Running,
+
+    /// Made it to the exit.
+    Solved,
+
+    /// Came back to where it already was, facing the same way, so it would go around forever.
+    LoopDetected,
+
+    /// Ran out of options, or took too long.
+    GaveUp,
+}
+
+impl fmt::Display for AgentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AgentStatus::Running => write!(f, "still running"),
+            AgentStatus::Solved => write!(f, "solved"),
+            AgentStatus::LoopDetected => write!(f, "stuck in a loop"),
+            AgentStatus::GaveUp => write!(f, "gave up"),
+        }
+    }
+}
+
+/// A maze solving strategy that moves one step at a time.
+pub trait SolverAgent {
+    /// The name used to pick this agent at runtime.
+    fn name(&self) -> &'static str;
+
+    /// Takes one step. Does nothing once the agent has stopped running.
+    fn step(&mut self, rng: &mut StdRng, grid: &CellGrid) -> AgentStatus;
+
+    fn status(&self) -> AgentStatus;
+
+    /// How many steps the agent has taken.
+    fn steps(&self) -> usize;
+
+    /// How the given cell should be highlighted, if at all.
+    fn highlight(&self, point: &XY) -> Option<Highlight>;
+}
+
+/// Steps an agent until it stops running, and returns how it ended up.
+pub fn run_to_completion(agent: &mut dyn SolverAgent, rng: &mut StdRng, grid: &CellGrid) -> AgentStatus {
+    loop {
+        let status = agent.step(rng, grid);
+        if status != AgentStatus::Running {
+            return status;
+        }
+    }
+}
+
+/// The cells next to `point` that aren't blocked by a wall.
+fn open_neighbors(grid: &CellGrid, point: &XY) -> Vec<XY> {
+    maze_neighbors(grid, point).into_iter().filter(|n| !has_wall_between(grid, point, n)).collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Facing {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Facing {
+    const ALL: [Facing; 4] = [Facing::North, Facing::East, Facing::South, Facing::West];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn turned_right(self) -> Facing {
+        Self::ALL[(self.index() + 1) % 4]
+    }
+
+    fn turned_left(self) -> Facing {
+        Self::ALL[(self.index() + 3) % 4]
+    }
+
+    fn reversed(self) -> Facing {
+        Self::ALL[(self.index() + 2) % 4]
+    }
+
+    // The neighboring maze cell in this direction, if there's no wall in the way.
+    fn step(self, grid: &CellGrid, point: &XY) -> Option<XY> {
+        let XY(x, y) = *point;
+        let next = match self {
+            Facing::North if y < grid.height() - 2 => XY(x, y + 1),
+            Facing::East if x < grid.width() - 2 => XY(x + 1, y),
+            Facing::South if y > 0 => XY(x, y - 1),
+            Facing::West if x > 0 => XY(x - 1, y),
+            _ => return None,
+        };
+
+        if has_wall_between(grid, point, &next) { None } else { Some(next) }
+    }
+}
+
+/// Which hand the wall follower keeps on the wall.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// Keeps one hand on the wall and follows it. This always works in a perfect maze, but in a maze
+/// with loops it can end up circling an island forever, which is detected by coming back to the
+/// same cell facing the same way. That can only happen when the wall it starts on is an island,
+/// so an entrance on the outer border is always safe.
+pub struct WallFollower {
+    hand: Hand,
+    position: XY,
+    facing: Facing,
+    end: XY,
+
+    // Which directions the follower has already left each cell facing.
+    seen: Grid<[bool; 4]>,
+    steps: usize,
+    status: AgentStatus,
+}
+
+impl WallFollower {
+    pub fn new(grid: &CellGrid, start: XY, end: XY, hand: Hand) -> WallFollower {
+        // Start off facing into the maze, away from whichever border the entrance is on.
+        let XY(x, y) = start;
+        let facing =
+            if y == 0 && !grid[&start].has_bottom_edge() {
+                Facing::North
+            }
+            else if x == 0 && !grid[&start].has_left_edge() {
+                Facing::East
+            }
+            else if x == grid.width() - 2 && !grid[XY(x + 1, y)].has_left_edge() {
+                Facing::West
+            }
+            else {
+                Facing::South
+            };
+
+        WallFollower {
+            hand,
+            position: start,
+            facing,
+            end,
+            seen: Grid::new(grid.width(), grid.height(), &[false; 4]),
+            steps: 0,
+            status: AgentStatus::Running,
+        }
+    }
+}
+
+impl SolverAgent for WallFollower {
+    fn name(&self) -> &'static str {
+        match self.hand {
+            Hand::Left => "left-hand",
+            Hand::Right => "right-hand",
+        }
+    }
+
+    fn step(&mut self, _rng: &mut StdRng, grid: &CellGrid) -> AgentStatus {
+        if self.status != AgentStatus::Running {
+            return self.status.clone();
+        }
+
+        if self.position == self.end {
+            self.status = AgentStatus::Solved;
+            return self.status.clone();
+        }
+
+        // Try the hand side first, then straight on, then the other side, then back.
+        let (toward_hand, away_from_hand) = match self.hand {
+            Hand::Left => (self.facing.turned_left(), self.facing.turned_right()),
+            Hand::Right => (self.facing.turned_right(), self.facing.turned_left()),
+        };
+
+        let choices = [toward_hand, self.facing, away_from_hand, self.facing.reversed()];
+        let next = choices.iter().find_map(|facing| facing.step(grid, &self.position).map(|next| (*facing, next)));
+        let (facing, next) = match next {
+            Some(next) => next,
+            None => {
+                // Walled in on every side.
+                self.status = AgentStatus::GaveUp;
+                return self.status.clone();
+            },
+        };
+
+        if self.seen[&self.position][facing.index()] {
+            self.status = AgentStatus::LoopDetected;
+            return self.status.clone();
+        }
+
+        self.seen[&self.position][facing.index()] = true;
+        self.position = next;
+        self.facing = facing;
+        self.steps += 1;
+        self.status.clone()
+    }
+
+    fn status(&self) -> AgentStatus {
+        self.status.clone()
+    }
+
+    fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if *point == self.position {
+            Some(Highlight::Current)
+        }
+        else if self.seen[point].iter().any(|&seen| seen) {
+            Some(Highlight::Walk)
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// Trémaux's algorithm: marks every passage each time it's walked through. At a junction it takes
+/// an unmarked passage if it can, or one marked once otherwise, and never one marked twice. When
+/// it walks into a junction it has already been to along a new passage, it turns straight back.
+/// This finds the exit in any maze, and gives up only if there is no way out.
+pub struct TremauxAgent {
+    position: XY,
+    came_from: Option<XY>,
+    end: XY,
+
+    // How many times each passage has been walked, stored against the cell that owns the wall: left
+    // wall first, then bottom wall, the same way the grid stores walls.
+    marks: Grid<[u8; 2]>,
+    steps: usize,
+    status: AgentStatus,
+}
+
+impl TremauxAgent {
+    pub fn new(grid: &CellGrid, start: XY, end: XY) -> TremauxAgent {
+        TremauxAgent {
+            position: start,
+            came_from: None,
+            end,
+            marks: Grid::new(grid.width(), grid.height(), &[0, 0]),
+            steps: 0,
+            status: AgentStatus::Running,
+        }
+    }
+
+    fn mark_slot(a: &XY, b: &XY) -> (XY, usize) {
+        if a.1 == b.1 {
+            (if a.0 > b.0 { a.clone() } else { b.clone() }, 0)
+        }
+        else {
+            (if a.1 > b.1 { a.clone() } else { b.clone() }, 1)
+        }
+    }
+
+    fn marks_between(&self, a: &XY, b: &XY) -> u8 {
+        let (owner, slot) = Self::mark_slot(a, b);
+        self.marks[&owner][slot]
+    }
+
+    fn walk_to(&mut self, next: XY) {
+        let (owner, slot) = Self::mark_slot(&self.position, &next);
+        self.marks[&owner][slot] += 1;
+        self.came_from = Some(self.position.clone());
+        self.position = next;
+        self.steps += 1;
+    }
+}
+
+impl SolverAgent for TremauxAgent {
+    fn name(&self) -> &'static str {
+        "tremaux"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &CellGrid) -> AgentStatus {
+        if self.status != AgentStatus::Running {
+            return self.status.clone();
+        }
+
+        if self.position == self.end {
+            self.status = AgentStatus::Solved;
+            return self.status.clone();
+        }
+
+        let neighbors = open_neighbors(grid, &self.position);
+        let others: Vec<XY> = neighbors.into_iter().filter(|n| Some(n) != self.came_from.as_ref()).collect();
+
+        // Walked into somewhere already visited along a fresh passage: go back the way we came.
+        if let Some(ref came_from) = self.came_from {
+            let been_here = others.iter().any(|n| self.marks_between(&self.position, n) > 0);
+            if been_here && self.marks_between(&self.position, came_from) == 1 {
+                let back = came_from.clone();
+                self.walk_to(back);
+                return self.status.clone();
+            }
+        }
+
+        for marks in 0 .. 2 {
+            let choices: Vec<&XY> = others.iter().filter(|n| self.marks_between(&self.position, n) == marks).collect();
+            if !choices.is_empty() {
+                let next = choices[rng.gen_range(0, choices.len())].clone();
+                self.walk_to(next);
+                return self.status.clone();
+            }
+        }
+
+        // A dead end, so back out, unless that passage has already been used up.
+        match self.came_from.clone() {
+            Some(back) if self.marks_between(&self.position, &back) < 2 => self.walk_to(back),
+            _ => self.status = AgentStatus::GaveUp,
+        }
+
+        self.status.clone()
+    }
+
+    fn status(&self) -> AgentStatus {
+        self.status.clone()
+    }
+
+    fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if *point == self.position {
+            return Some(Highlight::Current);
+        }
+
+        // Show cells next to a passage marked once as the trail, and ones marked twice as done.
+        let XY(x, y) = *point;
+        let mut marks = vec![self.marks[point][0], self.marks[point][1]];
+        if x + 1 < self.marks.width() {
+            marks.push(self.marks[XY(x + 1, y)][0]);
+        }
+
+        if y + 1 < self.marks.height() {
+            marks.push(self.marks[XY(x, y + 1)][1]);
+        }
+
+        if marks.contains(&1) {
+            Some(Highlight::Walk)
+        }
+        else if marks.contains(&2) {
+            Some(Highlight::Scan)
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// Fills in dead ends one cell at a time, other than the entrance and exit. Filling in a dead end
+/// can make the cell next to it a dead end too. When there are none left, what's still open is the
+/// way through.
+pub struct DeadEndFiller {
+    start: XY,
+    end: XY,
+    filled: Grid<bool>,
+    dead_ends: VecDeque<XY>,
+    last_filled: Option<XY>,
+    steps: usize,
+    status: AgentStatus,
+}
+
+impl DeadEndFiller {
+    pub fn new(grid: &CellGrid, start: XY, end: XY) -> DeadEndFiller {
+        let mut filler = DeadEndFiller {
+            start,
+            end,
+            filled: Grid::new(grid.width(), grid.height(), &false),
+            dead_ends: VecDeque::new(),
+            last_filled: None,
+            steps: 0,
+            status: AgentStatus::Running,
+        };
+
+        for y in 0 .. grid.height() - 1 {
+            for x in 0 .. grid.width() - 1 {
+                let point = XY(x, y);
+                if filler.is_dead_end(grid, &point) {
+                    filler.dead_ends.push_back(point);
+                }
+            }
+        }
+
+        filler
+    }
+
+    fn open_unfilled_neighbors(&self, grid: &CellGrid, point: &XY) -> Vec<XY> {
+        open_neighbors(grid, point).into_iter().filter(|n| !self.filled[n]).collect()
+    }
+
+    // Culled cells aren't part of the maze at all, so they never count as dead ends.
+    fn is_dead_end(&self, grid: &CellGrid, point: &XY) -> bool {
+        *point != self.start && *point != self.end && !self.filled[point] &&
+            !matches!(grid[point].kind, GridCellKind::Removed) &&
+            self.open_unfilled_neighbors(grid, point).len() <= 1
+    }
+
+    // Whether the cells left open still join the entrance to the exit.
+    fn has_open_route(&self, grid: &CellGrid) -> bool {
+        let mut reached = Grid::new(grid.width(), grid.height(), &false);
+        let mut queue = VecDeque::new();
+        reached[&self.start] = true;
+        queue.push_back(self.start.clone());
+
+        while let Some(point) = queue.pop_front() {
+            if point == self.end {
+                return true;
+            }
+
+            for neighbor in self.open_unfilled_neighbors(grid, &point) {
+                if !reached[&neighbor] {
+                    reached[&neighbor] = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl SolverAgent for DeadEndFiller {
+    fn name(&self) -> &'static str {
+        "dead-end-filling"
+    }
+
+    fn step(&mut self, _rng: &mut StdRng, grid: &CellGrid) -> AgentStatus {
+        if self.status != AgentStatus::Running {
+            return self.status.clone();
+        }
+
+        // Fill in one dead end per step. Some in the queue may have been filled or opened up
+        // since they were added.
+        while let Some(point) = self.dead_ends.pop_front() {
+            if !self.is_dead_end(grid, &point) {
+                continue;
+            }
+
+            let neighbors = self.open_unfilled_neighbors(grid, &point);
+            self.filled[&point] = true;
+            self.last_filled = Some(point);
+            self.steps += 1;
+
+            for neighbor in neighbors {
+                if self.is_dead_end(grid, &neighbor) {
+                    self.dead_ends.push_back(neighbor);
+                }
+            }
+
+            return self.status.clone();
+        }
+
+        self.last_filled = None;
+        self.status = if self.has_open_route(grid) { AgentStatus::Solved } else { AgentStatus::GaveUp };
+        self.status.clone()
+    }
+
+    fn status(&self) -> AgentStatus {
+        self.status.clone()
+    }
+
+    fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if self.last_filled.as_ref() == Some(point) {
+            Some(Highlight::Current)
+        }
+        else if self.filled[point] {
+            Some(Highlight::Scan)
+        }
+        else if self.status == AgentStatus::Solved {
+            Some(Highlight::Walk)
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// How many steps the random mouse gets per maze cell before giving up.
+const RANDOM_MOUSE_STEPS_PER_CELL: usize = 100;
+
+/// Wanders at random, only turning back at dead ends. It gets there eventually, but it can take a
+/// very long time, so it gives up after a while.
+pub struct RandomMouse {
+    position: XY,
+    came_from: Option<XY>,
+    end: XY,
+    max_steps: usize,
+    steps: usize,
+    status: AgentStatus,
+}
+
+impl RandomMouse {
+    pub fn new(grid: &CellGrid, start: XY, end: XY) -> RandomMouse {
+        RandomMouse {
+            position: start,
+            came_from: None,
+            end,
+            max_steps: (grid.width() - 1) * (grid.height() - 1) * RANDOM_MOUSE_STEPS_PER_CELL,
+            steps: 0,
+            status: AgentStatus::Running,
+        }
+    }
+}
+
+impl SolverAgent for RandomMouse {
+    fn name(&self) -> &'static str {
+        "random-mouse"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &CellGrid) -> AgentStatus {
+        if self.status != AgentStatus::Running {
+            return self.status.clone();
+        }
+
+        if self.position == self.end {
+            self.status = AgentStatus::Solved;
+            return self.status.clone();
+        }
+
+        if self.steps >= self.max_steps {
+            self.status = AgentStatus::GaveUp;
+            return self.status.clone();
+        }
+
+        let neighbors = open_neighbors(grid, &self.position);
+        let forward: Vec<&XY> = neighbors.iter().filter(|n| Some(*n) != self.came_from.as_ref()).collect();
+        let next = if !forward.is_empty() {
+            forward[rng.gen_range(0, forward.len())].clone()
+        }
+        else if let Some(ref back) = self.came_from {
+            back.clone()
+        }
+        else {
+            self.status = AgentStatus::GaveUp;
+            return self.status.clone();
+        };
+
+        self.came_from = Some(self.position.clone());
+        self.position = next;
+        self.steps += 1;
+        self.status.clone()
+    }
+
+    fn status(&self) -> AgentStatus {
+        self.status.clone()
+    }
+
+    fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if *point == self.position { Some(Highlight::Current) } else { None }
+    }
+}
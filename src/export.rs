@@ -0,0 +1,187 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::grid::XY;
+use crate::maze::{CellGrid, GridState};
+use crate::render::{self, PixelSink, RenderOptions};
+
+/// Encodes a rasterized maze as a PNG into the given writer.
+pub fn write_png<W: Write>(writer: W, grid: &CellGrid, options: &RenderOptions) -> io::Result<()> {
+    let image = render::render_to_image(grid, options);
+
+    let mut encoder = png::Encoder::new(writer, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::RGB);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut png_writer = encoder.write_header()?;
+    png_writer.write_image_data(image.pixels())?;
+    Ok(())
+}
+
+/// Saves a finished maze to a PNG file. Fails if the maze is still being generated.
+pub fn save_png<P: AsRef<Path>>(path: P, maze: &GridState, options: &RenderOptions) -> io::Result<()> {
+    if !maze.is_done() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "maze generation is not done"));
+    }
+
+    // The PNG encoder writes its last chunk when it's dropped and throws away any error, so encode
+    // into memory first, where that can't fail, and then write the file.
+    let mut data = Vec::new();
+    write_png(&mut data, maze.grid(), options)?;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(&data)?;
+    writer.flush()
+}
+
+/// Sizes used when writing a maze as SVG. The values are in SVG user units, and the document is
+/// sized using `units`, so the same drawing can be scaled to any paper size.
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+    /// The size of one cell.
+    pub cell_size: f64,
+
+    /// The stroke width of each wall.
+    pub wall_thickness: f64,
+
+    /// Blank space around the whole maze.
+    pub margin: f64,
+
+    /// The unit suffix for the document's width and height, e.g. "px" or "mm".
+    pub units: String,
+
+    /// Whether to draw a polyline along the generated path.
+    pub draw_path: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            cell_size: 10.0,
+            wall_thickness: 1.0,
+            margin: 5.0,
+            units: String::from("mm"),
+            draw_path: false,
+        }
+    }
+}
+
+impl From<&RenderOptions> for SvgOptions {
+    fn from(options: &RenderOptions) -> Self {
+        SvgOptions {
+            cell_size: options.scale_in_px as f64,
+            wall_thickness: options.edge_thickness_in_px as f64,
+            margin: options.draw_offset_in_px as f64,
+            units: String::from("px"),
+            draw_path: options.draw_path,
+        }
+    }
+}
+
+/// A straight wall running along a grid line, in grid coordinates.
+struct WallSegment {
+    x1: usize,
+    y1: usize,
+    x2: usize,
+    y2: usize,
+}
+
+/// Collects every wall in the grid, joining collinear neighboring walls into one segment.
+fn merged_wall_segments(grid: &CellGrid) -> Vec<WallSegment> {
+    let mut segments = Vec::new();
+
+    // Horizontal walls: each grid line holds the bottom edges of one row of cells.
+    for y in 0 .. grid.height() {
+        let mut run_start = None;
+        for x in 0 .. grid.width() {
+            let has_edge = x < grid.width() - 1 && grid[XY(x, y)].has_bottom_edge();
+            match (has_edge, run_start) {
+                (true, None) => run_start = Some(x),
+                (false, Some(start)) => {
+                    segments.push(WallSegment { x1: start, y1: y, x2: x, y2: y });
+                    run_start = None;
+                },
+                _ => (),
+            }
+        }
+    }
+
+    // Vertical walls: each grid column holds the left edges of one column of cells.
+    for x in 0 .. grid.width() {
+        let mut run_start = None;
+        for y in 0 .. grid.height() {
+            let has_edge = y < grid.height() - 1 && grid[XY(x, y)].has_left_edge();
+            match (has_edge, run_start) {
+                (true, None) => run_start = Some(y),
+                (false, Some(start)) => {
+                    segments.push(WallSegment { x1: x, y1: start, x2: x, y2: y });
+                    run_start = None;
+                },
+                _ => (),
+            }
+        }
+    }
+
+    segments
+}
+
+/// Writes a maze as an SVG document into the given writer.
+pub fn write_svg<W: Write>(mut writer: W, grid: &CellGrid, options: &SvgOptions) -> io::Result<()> {
+    // SVG has y going down, but the grid has y going up.
+    let to_svg_x = |x: f64| options.margin + (x * options.cell_size);
+    let to_svg_y = |y: f64| options.margin + (((grid.height() - 1) as f64 - y) * options.cell_size);
+
+    let width = (2.0 * options.margin) + ((grid.width() - 1) as f64 * options.cell_size);
+    let height = (2.0 * options.margin) + ((grid.height() - 1) as f64 * options.cell_size);
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}{u}" height="{h}{u}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height,
+        u = options.units)?;
+    writeln!(writer, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+
+    writeln!(
+        writer,
+        r#"<g stroke="black" stroke-width="{}" stroke-linecap="square">"#,
+        options.wall_thickness)?;
+    for segment in merged_wall_segments(grid) {
+        writeln!(
+            writer,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+            to_svg_x(segment.x1 as f64),
+            to_svg_y(segment.y1 as f64),
+            to_svg_x(segment.x2 as f64),
+            to_svg_y(segment.y2 as f64))?;
+    }
+    writeln!(writer, "</g>")?;
+
+    if options.draw_path {
+        // Run the line through the middle of each cell on the path.
+        let points: Vec<String> = GridState::extract_path(grid).iter().map(|XY(x, y)| {
+            format!("{},{}", to_svg_x(*x as f64 + 0.5), to_svg_y(*y as f64 + 0.5))
+        }).collect();
+
+        writeln!(
+            writer,
+            r#"<polyline points="{}" fill="none" stroke="red" stroke-width="{}" stroke-linejoin="round"/>"#,
+            points.join(" "),
+            options.wall_thickness)?;
+    }
+
+    writeln!(writer, "</svg>")
+}
+
+/// Saves a finished maze to an SVG file. Fails if the maze is still being generated.
+pub fn save_svg<P: AsRef<Path>>(path: P, maze: &GridState, options: &SvgOptions) -> io::Result<()> {
+    if !maze.is_done() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "maze generation is not done"));
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_svg(&mut writer, maze.grid(), options)?;
+    writer.flush()
+}
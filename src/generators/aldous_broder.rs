@@ -0,0 +1,163 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    fill_all_walls,
+    maze_neighbors,
+    open_random_entrance_and_exit,
+    random_maze_cell,
+    set_wall_between,
+    wilson::LoopErasedWalker,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// Builds a maze chosen uniformly from all possible perfect mazes with the Aldous-Broder
+/// algorithm: a random walk over the whole grid that carves a passage whenever it enters a cell it
+/// hasn't been to before.
+///
+/// The walk is slow to find the last few unvisited cells, so in hybrid mode it switches over to
+/// Wilson's loop-erased walks once enough of the grid has been visited.
+pub struct AldousBroderGenerator {
+    // The fraction of cells to visit before switching to Wilson's algorithm, if at all.
+    switch_fraction: Option<f64>,
+    state: Option<WalkState>,
+}
+
+enum WalkState {
+    Random {
+        visited: Grid<bool>,
+        visited_count: usize,
+        current: XY,
+        walk_steps: usize,
+    },
+
+    LoopErased {
+        walker: LoopErasedWalker,
+        random_walk_steps: usize,
+
+        // How many cells the random walk had visited when it switched over.
+        switched_at: usize,
+    },
+}
+
+impl AldousBroderGenerator {
+    pub fn new() -> AldousBroderGenerator {
+        AldousBroderGenerator {
+            switch_fraction: None,
+            state: None,
+        }
+    }
+
+    /// A generator that switches to Wilson's algorithm once `switch_fraction` of the cells have
+    /// been visited.
+    pub fn hybrid(switch_fraction: f64) -> AldousBroderGenerator {
+        AldousBroderGenerator {
+            switch_fraction: Some(switch_fraction),
+            state: None,
+        }
+    }
+
+    /// How many random steps have been taken in total, across both kinds of walk.
+    pub fn walk_steps(&self) -> usize {
+        match self.state {
+            None => 0,
+            Some(WalkState::Random { walk_steps, .. }) => walk_steps,
+            Some(WalkState::LoopErased { ref walker, random_walk_steps, .. }) => random_walk_steps + walker.walk_steps(),
+        }
+    }
+}
+
+impl Default for AldousBroderGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MazeGenerator for AldousBroderGenerator {
+    fn name(&self) -> &'static str {
+        if self.switch_fraction.is_some() { "aldous-broder-wilson" } else { "aldous-broder" }
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let cell_count = (grid.width() - 1) * (grid.height() - 1);
+
+        match self.state {
+            None => {
+                fill_all_walls(grid);
+
+                let mut visited = Grid::new(grid.width(), grid.height(), &false);
+                let current = random_maze_cell(rng, grid);
+                visited[&current] = true;
+                self.state = Some(WalkState::Random { visited, visited_count: 1, current, walk_steps: 0 });
+                StepResult::Pause(250)
+            },
+
+            Some(WalkState::Random { ref mut visited, ref mut visited_count, ref mut current, ref mut walk_steps }) => {
+                if *visited_count == cell_count {
+                    open_random_entrance_and_exit(rng, grid);
+                    return StepResult::Done;
+                }
+
+                let should_switch = self.switch_fraction.is_some_and(|fraction| {
+                    *visited_count as f64 >= fraction * cell_count as f64
+                });
+
+                if should_switch {
+                    let walker = LoopErasedWalker::new(rng, grid, visited.clone());
+                    self.state = Some(WalkState::LoopErased { walker, random_walk_steps: *walk_steps, switched_at: *visited_count });
+                    return StepResult::Pause(250);
+                }
+
+                // Keep walking until the walk reaches a new cell, so that each step carves something.
+                loop {
+                    let neighbors = maze_neighbors(grid, current);
+                    let next = neighbors[rng.gen_range(0, neighbors.len())].clone();
+                    *walk_steps += 1;
+
+                    let is_new = !visited[&next];
+                    if is_new {
+                        set_wall_between(grid, current, &next, EdgeState::Off);
+                        visited[&next] = true;
+                        *visited_count += 1;
+                    }
+
+                    *current = next;
+                    if is_new {
+                        return StepResult::Continue;
+                    }
+                }
+            },
+
+            Some(WalkState::LoopErased { ref mut walker, .. }) => {
+                if walker.step(rng, grid) {
+                    StepResult::Continue
+                }
+                else {
+                    open_random_entrance_and_exit(rng, grid);
+                    StepResult::Done
+                }
+            },
+        }
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        match self.state {
+            Some(WalkState::Random { ref current, .. }) if current == point => Some(Highlight::Current),
+            Some(WalkState::LoopErased { ref walker, .. }) => walker.highlight(point),
+            _ => None,
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        match self.state {
+            Some(WalkState::LoopErased { switched_at, .. }) => {
+                Some(format!("{} walk steps, switched to loop-erased walks after visiting {} cells", self.walk_steps(), switched_at))
+            },
+            _ => Some(format!("{} walk steps", self.walk_steps())),
+        }
+    }
+}
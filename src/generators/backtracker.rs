@@ -0,0 +1,106 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    fill_all_walls,
+    maze_neighbors,
+    open_random_entrance_and_exit,
+    random_maze_cell,
+    set_wall_between,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// Carves a perfect maze with a randomized depth-first search. From the current cell, it knocks
+/// down the wall to a random unvisited neighbor and moves there, backing up along its trail when
+/// it gets stuck.
+pub struct BacktrackerGenerator {
+    stack: Vec<XY>,
+    visited: Option<Grid<bool>>,
+
+    // Which cells are on the stack, so highlighting doesn't have to search it.
+    on_stack: Option<Grid<bool>>,
+}
+
+impl BacktrackerGenerator {
+    pub fn new() -> BacktrackerGenerator {
+        BacktrackerGenerator {
+            stack: Vec::new(),
+            visited: None,
+            on_stack: None,
+        }
+    }
+}
+
+impl Default for BacktrackerGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MazeGenerator for BacktrackerGenerator {
+    fn name(&self) -> &'static str {
+        "backtracker"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let visited = match self.visited {
+            Some(ref mut visited) => visited,
+            None => {
+                // First step: start from solid walls and a random cell.
+                fill_all_walls(grid);
+
+                let mut visited = Grid::new(grid.width(), grid.height(), &false);
+                let mut on_stack = Grid::new(grid.width(), grid.height(), &false);
+                let start = random_maze_cell(rng, grid);
+                visited[&start] = true;
+                on_stack[&start] = true;
+                self.stack.push(start);
+                self.visited = Some(visited);
+                self.on_stack = Some(on_stack);
+                return StepResult::Pause(250);
+            },
+        };
+
+        let on_stack = self.on_stack.as_mut().unwrap();
+        let current = match self.stack.last() {
+            Some(current) => current.clone(),
+            None => return StepResult::Done,
+        };
+
+        let unvisited: Vec<XY> = maze_neighbors(grid, &current).into_iter().filter(|n| !visited[n]).collect();
+        if unvisited.is_empty() {
+            self.stack.pop();
+            on_stack[&current] = false;
+
+            if self.stack.is_empty() {
+                open_random_entrance_and_exit(rng, grid);
+                return StepResult::Done;
+            }
+        }
+        else {
+            let next = unvisited[rng.gen_range(0, unvisited.len())].clone();
+            set_wall_between(grid, &current, &next, EdgeState::Off);
+            visited[&next] = true;
+            on_stack[&next] = true;
+            self.stack.push(next);
+        }
+
+        StepResult::Continue
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if self.stack.last() == Some(point) {
+            Some(Highlight::Current)
+        }
+        else if self.on_stack.as_ref().is_some_and(|on_stack| on_stack[point]) {
+            Some(Highlight::Frontier)
+        }
+        else {
+            None
+        }
+    }
+}
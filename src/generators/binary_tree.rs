@@ -0,0 +1,92 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::XY;
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    fill_all_walls,
+    open_random_entrance_and_exit,
+    set_wall_between,
+    Bias,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// Makes a perfect maze with the binary tree algorithm. Every cell knocks down the wall towards one
+/// of the two directions of the bias, picked at random. Each cell only needs a single decision, so
+/// it's very fast, but the two walls along the biased edges always end up as long corridors.
+pub struct BinaryTreeGenerator {
+    bias: Bias,
+
+    // How many cells have been visited, in the order given by `Bias::scan_cell`.
+    next_index: Option<usize>,
+    current: Option<XY>,
+}
+
+impl BinaryTreeGenerator {
+    pub fn new(bias: Bias) -> BinaryTreeGenerator {
+        BinaryTreeGenerator {
+            bias,
+            next_index: None,
+            current: None,
+        }
+    }
+}
+
+impl Default for BinaryTreeGenerator {
+    fn default() -> Self {
+        Self::new(Bias::default())
+    }
+}
+
+impl MazeGenerator for BinaryTreeGenerator {
+    fn name(&self) -> &'static str {
+        "binary-tree"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let index = match self.next_index {
+            Some(index) => index,
+            None => {
+                fill_all_walls(grid);
+                self.next_index = Some(0);
+                return StepResult::Pause(250);
+            },
+        };
+
+        if index == (grid.width() - 1) * (grid.height() - 1) {
+            self.current = None;
+            open_random_entrance_and_exit(rng, grid);
+            return StepResult::Done;
+        }
+
+        let point = self.bias.scan_cell(grid, index);
+        let choices: Vec<XY> = self.bias.vertical_neighbor(grid, &point).into_iter()
+            .chain(self.bias.horizontal_neighbor(grid, &point))
+            .collect();
+
+        // Only the corner cell the bias points to has nowhere to go.
+        if !choices.is_empty() {
+            let next = &choices[rng.gen_range(0, choices.len())];
+            set_wall_between(grid, &point, next, EdgeState::Off);
+        }
+
+        self.next_index = Some(index + 1);
+        self.current = Some(point);
+        StepResult::Continue
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if self.current.as_ref() == Some(point) {
+            Some(Highlight::Current)
+        }
+        else {
+            None
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        Some(format!("bias {}", self.bias))
+    }
+}
@@ -0,0 +1,55 @@
+/// A union-find structure over the numbers `0 .. len`, used to track which cells are already
+/// connected to each other.
+pub(crate) struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+}
+
+impl DisjointSet {
+    pub(crate) fn new(len: usize) -> DisjointSet {
+        DisjointSet {
+            parents: (0 .. len).collect(),
+            ranks: vec![0; len],
+        }
+    }
+
+    /// The representative of the set containing `item`.
+    pub(crate) fn find(&mut self, item: usize) -> usize {
+        let mut root = item;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Point everything along the way straight at the root so later lookups are quick.
+        let mut current = item;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns false if they were already in the same set.
+    pub(crate) fn union(&mut self, a: usize, b: usize) -> bool {
+        let root_a = self.find(a);
+        let root_b = self.find(b);
+        if root_a == root_b {
+            return false;
+        }
+
+        if self.ranks[root_a] < self.ranks[root_b] {
+            self.parents[root_a] = root_b;
+        }
+        else if self.ranks[root_a] > self.ranks[root_b] {
+            self.parents[root_b] = root_a;
+        }
+        else {
+            self.parents[root_b] = root_a;
+            self.ranks[root_a] += 1;
+        }
+
+        true
+    }
+}
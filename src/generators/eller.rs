@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::grid::XY;
+use crate::maze::{CellGrid, EdgeState, GridCell};
+use super::{
+    open_random_entrance_and_exit,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+const JOIN_CHANCE: f64 = 0.5;
+const CARVE_UP_CHANCE: f64 = 0.5;
+
+/// Generates a perfect maze one row at a time with Eller's algorithm, keeping only the current
+/// row's state in memory. That means mazes can be as tall as needed, or never end at all.
+///
+/// Each row is the same shape as a row of a [`CellGrid`]: one cell per maze column plus an extra
+/// cell that holds the right border, starting from the bottom row. When the maze has a height, one
+/// more row is produced at the end to hold the top border. The borders are left closed; it's up to
+/// the caller to open an entrance and exit.
+pub struct EllerRows {
+    width: usize,
+    height: Option<usize>,
+    row: usize,
+
+    // Which set each cell in the current row belongs to. Cells in the same set are connected.
+    sets: Vec<usize>,
+    next_set: usize,
+
+    // Which cells of the current row were carved into from the row below.
+    carved_from_below: Vec<bool>,
+}
+
+impl EllerRows {
+    /// Prepares to generate a maze `width` cells wide and either `height` cells tall or unbounded.
+    pub fn new(width: usize, height: Option<usize>) -> EllerRows {
+        EllerRows {
+            width,
+            height,
+            row: 0,
+            sets: (0 .. width).collect(),
+            next_set: width,
+            carved_from_below: vec![false; width],
+        }
+    }
+
+    /// The rows produced so far, not counting the top border row.
+    pub fn rows_generated(&self) -> usize {
+        self.row
+    }
+
+    fn merge_sets(&mut self, from: usize, into: usize) {
+        for set in self.sets.iter_mut() {
+            if *set == from {
+                *set = into;
+            }
+        }
+    }
+
+    fn top_border_row(&self) -> Vec<GridCell> {
+        let mut cells = vec![GridCell::new(); self.width + 1];
+        for cell in cells.iter_mut().take(self.width) {
+            cell.bottom_edge = EdgeState::On;
+        }
+
+        cells
+    }
+
+    /// Produces the next row, or `None` once the top border row has been produced.
+    pub fn next_row(&mut self, rng: &mut StdRng) -> Option<Vec<GridCell>> {
+        match self.height {
+            Some(height) if self.row == height => {
+                self.row += 1;
+                return Some(self.top_border_row());
+            },
+            Some(height) if self.row > height => return None,
+            _ => (),
+        }
+
+        let is_last_row = self.height.is_some_and(|height| self.row == height - 1);
+        let mut cells = vec![GridCell::new(); self.width + 1];
+
+        // Walls below this row were decided when the previous row was generated.
+        for (x, cell) in cells.iter_mut().take(self.width).enumerate() {
+            cell.bottom_edge = if self.carved_from_below[x] { EdgeState::Off } else { EdgeState::On };
+        }
+
+        // Join neighboring cells in different sets at random. In the last row, every set has to
+        // be joined so the maze ends up connected.
+        cells[0].left_edge = EdgeState::On;
+        for (x, cell) in cells.iter_mut().enumerate().take(self.width).skip(1) {
+            let left_set = self.sets[x - 1];
+            let set = self.sets[x];
+            if left_set != set && (is_last_row || rng.gen_bool(JOIN_CHANCE)) {
+                self.merge_sets(set, left_set);
+                cell.left_edge = EdgeState::Off;
+            }
+            else {
+                cell.left_edge = EdgeState::On;
+            }
+        }
+
+        cells[self.width].left_edge = EdgeState::On;
+
+        if !is_last_row {
+            // Every set needs at least one way up into the next row, or it would be cut off.
+            let mut carve_up: Vec<bool> = (0 .. self.width).map(|_| rng.gen_bool(CARVE_UP_CHANCE)).collect();
+
+            let mut members = BTreeMap::<usize, Vec<usize>>::new();
+            for (x, set) in self.sets.iter().enumerate() {
+                members.entry(*set).or_default().push(x);
+            }
+
+            for cells_in_set in members.values() {
+                if !cells_in_set.iter().any(|&x| carve_up[x]) {
+                    carve_up[cells_in_set[rng.gen_range(0, cells_in_set.len())]] = true;
+                }
+            }
+
+            // Cells that weren't carved into start off in sets of their own.
+            for (set, carved) in self.sets.iter_mut().zip(carve_up.iter()) {
+                if !carved {
+                    *set = self.next_set;
+                    self.next_set += 1;
+                }
+            }
+
+            self.carved_from_below = carve_up;
+        }
+
+        self.row += 1;
+        Some(cells)
+    }
+}
+
+/// An iterator over the rows of an Eller's algorithm maze, with its own seeded RNG.
+pub struct EllerIter {
+    rows: EllerRows,
+    rng: StdRng,
+}
+
+impl Iterator for EllerIter {
+    type Item = Vec<GridCell>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next_row(&mut self.rng)
+    }
+}
+
+/// Streams the rows of a maze `width` cells wide, bottom row first. With no `height`, the iterator
+/// never ends.
+pub fn eller_rows(seed: u64, width: usize, height: Option<usize>) -> EllerIter {
+    EllerIter {
+        rows: EllerRows::new(width, height),
+        rng: StdRng::seed_from_u64(seed),
+    }
+}
+
+/// Fills in a [`CellGrid`] one row per step using [`EllerRows`].
+pub struct EllerGenerator {
+    rows: Option<EllerRows>,
+}
+
+impl EllerGenerator {
+    pub fn new() -> EllerGenerator {
+        EllerGenerator {
+            rows: None,
+        }
+    }
+}
+
+impl Default for EllerGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MazeGenerator for EllerGenerator {
+    fn name(&self) -> &'static str {
+        "eller"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let rows = self.rows.get_or_insert_with(|| EllerRows::new(grid.width() - 1, Some(grid.height() - 1)));
+
+        let y = rows.rows_generated();
+        match rows.next_row(rng) {
+            Some(row) => {
+                for (x, cell) in row.into_iter().enumerate() {
+                    grid[XY(x, y)] = cell;
+                }
+                StepResult::Continue
+            },
+            None => {
+                open_random_entrance_and_exit(rng, grid);
+                StepResult::Done
+            },
+        }
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        // The row that was just generated.
+        match self.rows {
+            Some(ref rows) if rows.rows_generated() == point.1 + 1 => Some(Highlight::Current),
+            _ => None,
+        }
+    }
+}
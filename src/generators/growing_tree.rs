@@ -0,0 +1,249 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    fill_all_walls,
+    maze_neighbors,
+    open_random_entrance_and_exit,
+    random_maze_cell,
+    set_wall_between,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// Which of the active cells the growing-tree generator works from next.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellPick {
+    /// The most recently added cell, which makes long winding corridors like the backtracker.
+    Newest,
+
+    /// The cell that has been active the longest, which makes long straight runs from the start.
+    Oldest,
+
+    /// Any active cell, which makes lots of short branches like Prim's algorithm.
+    Random,
+}
+
+impl CellPick {
+    fn name(self) -> &'static str {
+        match self {
+            CellPick::Newest => "newest",
+            CellPick::Oldest => "oldest",
+            CellPick::Random => "random",
+        }
+    }
+}
+
+// The sum of all the weights, or `None` if it doesn't fit in a `u32`.
+fn total_weight(picks: &[(CellPick, u32)]) -> Option<u32> {
+    picks.iter().try_fold(0u32, |total, (_, weight)| total.checked_add(*weight))
+}
+
+/// A weighted mix of ways to pick the next active cell. Each step, one [`CellPick`] is chosen with
+/// a chance proportional to its weight.
+///
+/// Written as a single pick name like `newest`, or a comma-separated list of weighted picks like
+/// `newest:75,random:25`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrowingTreePolicy {
+    picks: Vec<(CellPick, u32)>,
+}
+
+impl GrowingTreePolicy {
+    /// A policy that always uses the same pick.
+    pub fn single(pick: CellPick) -> GrowingTreePolicy {
+        GrowingTreePolicy {
+            picks: vec![(pick, 1)],
+        }
+    }
+
+    /// A policy that mixes picks by weight. Picks with a weight of zero are never used. Returns
+    /// `None` if there is nothing with a weight above zero, or if the weights add up to more than
+    /// fits in a `u32`.
+    pub fn weighted(picks: &[(CellPick, u32)]) -> Option<GrowingTreePolicy> {
+        let picks: Vec<(CellPick, u32)> = picks.iter().cloned().filter(|(_, weight)| *weight > 0).collect();
+        if picks.is_empty() || total_weight(&picks).is_none() {
+            None
+        }
+        else {
+            Some(GrowingTreePolicy {
+                picks,
+            })
+        }
+    }
+
+    /// Chooses the index of the next cell to work from, out of `active_count` active cells.
+    fn choose(&self, rng: &mut StdRng, active_count: usize) -> usize {
+        let total = total_weight(&self.picks).unwrap();
+        let mut roll = rng.gen_range(0, total);
+        let mut pick = self.picks[0].0;
+        for (candidate, weight) in self.picks.iter() {
+            if roll < *weight {
+                pick = *candidate;
+                break;
+            }
+
+            roll -= weight;
+        }
+
+        match pick {
+            CellPick::Newest => active_count - 1,
+            CellPick::Oldest => 0,
+            CellPick::Random => rng.gen_range(0, active_count),
+        }
+    }
+}
+
+impl Default for GrowingTreePolicy {
+    fn default() -> Self {
+        GrowingTreePolicy {
+            picks: vec![(CellPick::Newest, 75), (CellPick::Random, 25)],
+        }
+    }
+}
+
+impl fmt::Display for GrowingTreePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let [(pick, _)] = self.picks.as_slice() {
+            return write!(f, "{}", pick.name());
+        }
+
+        let parts: Vec<String> = self.picks.iter().map(|(pick, weight)| format!("{}:{}", pick.name(), weight)).collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+impl FromStr for GrowingTreePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut picks = Vec::new();
+        for part in s.split(',') {
+            let mut pieces = part.trim().splitn(2, ':');
+            let pick = match pieces.next().unwrap_or("") {
+                "newest" => CellPick::Newest,
+                "oldest" => CellPick::Oldest,
+                "random" => CellPick::Random,
+                other => return Err(format!("unknown cell pick '{}'", other)),
+            };
+
+            let weight = match pieces.next() {
+                Some(weight) => weight.parse::<u32>().map_err(|_| format!("invalid weight '{}'", weight))?,
+                None => 1,
+            };
+
+            picks.push((pick, weight));
+        }
+
+        if total_weight(&picks).is_none() {
+            return Err(format!("weights must add up to at most {}", u32::MAX));
+        }
+
+        GrowingTreePolicy::weighted(&picks).ok_or_else(|| String::from("at least one weight must be above zero"))
+    }
+}
+
+/// Carves a perfect maze by keeping a list of active cells. Each step, the policy picks one of
+/// them, and a wall is knocked down to one of its unvisited neighbors, which becomes active too.
+/// Cells with no unvisited neighbors are dropped from the list.
+pub struct GrowingTreeGenerator {
+    policy: GrowingTreePolicy,
+    active: Vec<XY>,
+    visited: Option<Grid<bool>>,
+    current: Option<XY>,
+
+    // Which cells are in the active list, so highlighting doesn't have to search it.
+    is_active: Option<Grid<bool>>,
+}
+
+impl GrowingTreeGenerator {
+    pub fn new(policy: GrowingTreePolicy) -> GrowingTreeGenerator {
+        GrowingTreeGenerator {
+            policy,
+            active: Vec::new(),
+            visited: None,
+            current: None,
+            is_active: None,
+        }
+    }
+}
+
+impl Default for GrowingTreeGenerator {
+    fn default() -> Self {
+        Self::new(GrowingTreePolicy::default())
+    }
+}
+
+impl MazeGenerator for GrowingTreeGenerator {
+    fn name(&self) -> &'static str {
+        "growing-tree"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let visited = match self.visited {
+            Some(ref mut visited) => visited,
+            None => {
+                fill_all_walls(grid);
+
+                let mut visited = Grid::new(grid.width(), grid.height(), &false);
+                let mut is_active = Grid::new(grid.width(), grid.height(), &false);
+                let start = random_maze_cell(rng, grid);
+                visited[&start] = true;
+                is_active[&start] = true;
+                self.active.push(start);
+                self.visited = Some(visited);
+                self.is_active = Some(is_active);
+                return StepResult::Pause(250);
+            },
+        };
+
+        if self.active.is_empty() {
+            self.current = None;
+            open_random_entrance_and_exit(rng, grid);
+            return StepResult::Done;
+        }
+
+        let is_active = self.is_active.as_mut().unwrap();
+
+        // Keep order in the active list, since newest and oldest depend on it.
+        let index = self.policy.choose(rng, self.active.len());
+        let current = self.active[index].clone();
+        let unvisited: Vec<XY> = maze_neighbors(grid, &current).into_iter().filter(|n| !visited[n]).collect();
+        if unvisited.is_empty() {
+            self.active.remove(index);
+            is_active[&current] = false;
+        }
+        else {
+            let next = unvisited[rng.gen_range(0, unvisited.len())].clone();
+            set_wall_between(grid, &current, &next, EdgeState::Off);
+            visited[&next] = true;
+            is_active[&next] = true;
+            self.active.push(next);
+        }
+
+        self.current = Some(current);
+        StepResult::Continue
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if self.current.as_ref() == Some(point) {
+            Some(Highlight::Current)
+        }
+        else if self.is_active.as_ref().is_some_and(|is_active| is_active[point]) {
+            Some(Highlight::Frontier)
+        }
+        else {
+            None
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        Some(format!("policy {}", self.policy))
+    }
+}
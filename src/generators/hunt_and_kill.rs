@@ -0,0 +1,159 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    fill_all_walls,
+    maze_neighbors,
+    open_random_entrance_and_exit,
+    random_maze_cell,
+    set_wall_between,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// Carves a perfect maze by walking randomly into unvisited cells until stuck, then hunting row by
+/// row for an unvisited cell next to the visited part of the maze, and walking again from there.
+pub struct HuntAndKillGenerator {
+    state: Option<HuntState>,
+    hunts: usize,
+}
+
+enum Phase {
+    // Walking from this cell.
+    Kill(XY),
+
+    // Scanning this row for a new place to start walking.
+    Hunt(usize),
+
+    Done,
+}
+
+struct HuntState {
+    visited: Grid<bool>,
+    phase: Phase,
+
+    // Every row below this one has been fully visited, so hunts can start here.
+    first_open_row: usize,
+}
+
+impl HuntAndKillGenerator {
+    pub fn new() -> HuntAndKillGenerator {
+        HuntAndKillGenerator {
+            state: None,
+            hunts: 0,
+        }
+    }
+}
+
+impl Default for HuntAndKillGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HuntState {
+    fn walk(&mut self, rng: &mut StdRng, grid: &mut CellGrid, current: XY) {
+        let unvisited: Vec<XY> = maze_neighbors(grid, &current).into_iter().filter(|n| !self.visited[n]).collect();
+        if unvisited.is_empty() {
+            self.phase = Phase::Hunt(self.first_open_row);
+        }
+        else {
+            let next = unvisited[rng.gen_range(0, unvisited.len())].clone();
+            set_wall_between(grid, &current, &next, EdgeState::Off);
+            self.visited[&next] = true;
+            self.phase = Phase::Kill(next);
+        }
+    }
+
+    // Returns false once there are no rows left to scan.
+    fn hunt(&mut self, rng: &mut StdRng, grid: &mut CellGrid, y: usize) -> bool {
+        if y >= grid.height() - 1 {
+            return false;
+        }
+
+        let mut row_has_unvisited = false;
+        for x in 0 .. grid.width() - 1 {
+            let point = XY(x, y);
+            if self.visited[&point] {
+                continue;
+            }
+
+            row_has_unvisited = true;
+            let visited_neighbors: Vec<XY> = maze_neighbors(grid, &point).into_iter().filter(|n| self.visited[n]).collect();
+            if !visited_neighbors.is_empty() {
+                let neighbor = &visited_neighbors[rng.gen_range(0, visited_neighbors.len())];
+                set_wall_between(grid, &point, neighbor, EdgeState::Off);
+                self.visited[&point] = true;
+                self.phase = Phase::Kill(point);
+                return true;
+            }
+        }
+
+        if !row_has_unvisited && y == self.first_open_row {
+            self.first_open_row += 1;
+        }
+
+        self.phase = Phase::Hunt(y + 1);
+        true
+    }
+}
+
+impl MazeGenerator for HuntAndKillGenerator {
+    fn name(&self) -> &'static str {
+        "hunt-and-kill"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let state = match self.state {
+            Some(ref mut state) => state,
+            None => {
+                fill_all_walls(grid);
+
+                let mut visited = Grid::new(grid.width(), grid.height(), &false);
+                let start = random_maze_cell(rng, grid);
+                visited[&start] = true;
+                self.state = Some(HuntState {
+                    visited,
+                    phase: Phase::Kill(start),
+                    first_open_row: 0,
+                });
+                return StepResult::Pause(250);
+            },
+        };
+
+        match state.phase {
+            Phase::Kill(ref current) => {
+                let current = current.clone();
+                state.walk(rng, grid, current);
+                if let Phase::Hunt(_) = state.phase {
+                    self.hunts += 1;
+                }
+            },
+            Phase::Hunt(y) => {
+                if !state.hunt(rng, grid, y) {
+                    state.phase = Phase::Done;
+                    open_random_entrance_and_exit(rng, grid);
+                    return StepResult::Done;
+                }
+            },
+            Phase::Done => return StepResult::Done,
+        }
+
+        StepResult::Continue
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        match self.state.as_ref()?.phase {
+            Phase::Kill(ref current) if current == point => Some(Highlight::Current),
+            Phase::Hunt(y) if point.1 == y => Some(Highlight::Scan),
+            _ => None,
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        Some(format!("{} hunts", self.hunts))
+    }
+}
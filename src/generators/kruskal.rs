@@ -0,0 +1,104 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::grid::XY;
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    disjoint_set::DisjointSet,
+    fill_all_walls,
+    open_random_entrance_and_exit,
+    set_wall_between,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// Builds a perfect maze with randomized Kruskal's algorithm. Every inner wall starts out
+/// provisional and is visited in a random order. A wall is removed if the cells on either side
+/// aren't connected yet, and kept otherwise.
+pub struct KruskalGenerator {
+    // Inner walls that haven't been looked at yet, as the pair of cells they separate.
+    edges: Option<Vec<(XY, XY)>>,
+    sets: DisjointSet,
+    current: Option<(XY, XY)>,
+}
+
+impl KruskalGenerator {
+    pub fn new() -> KruskalGenerator {
+        KruskalGenerator {
+            edges: None,
+            sets: DisjointSet::new(0),
+            current: None,
+        }
+    }
+
+    fn cell_index(grid: &CellGrid, XY(x, y): &XY) -> usize {
+        (y * (grid.width() - 1)) + x
+    }
+}
+
+impl Default for KruskalGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MazeGenerator for KruskalGenerator {
+    fn name(&self) -> &'static str {
+        "kruskal"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let edges = match self.edges {
+            Some(ref mut edges) => edges,
+            None => {
+                // First step: wall off everything, but mark the inner walls as provisional until
+                // they're decided on.
+                fill_all_walls(grid);
+
+                let mut edges = Vec::new();
+                for y in 0 .. grid.height() - 1 {
+                    for x in 0 .. grid.width() - 1 {
+                        if x > 0 {
+                            edges.push((XY(x - 1, y), XY(x, y)));
+                        }
+
+                        if y > 0 {
+                            edges.push((XY(x, y - 1), XY(x, y)));
+                        }
+                    }
+                }
+
+                for (a, b) in edges.iter() {
+                    set_wall_between(grid, a, b, EdgeState::ProvisionallyOn);
+                }
+
+                edges.shuffle(rng);
+                self.sets = DisjointSet::new((grid.width() - 1) * (grid.height() - 1));
+                self.edges = Some(edges);
+                return StepResult::Pause(250);
+            },
+        };
+
+        match edges.pop() {
+            Some((a, b)) => {
+                let joined = self.sets.union(Self::cell_index(grid, &a), Self::cell_index(grid, &b));
+                set_wall_between(grid, &a, &b, if joined { EdgeState::Off } else { EdgeState::On });
+                self.current = Some((a, b));
+                StepResult::Continue
+            },
+            None => {
+                self.current = None;
+                open_random_entrance_and_exit(rng, grid);
+                StepResult::Done
+            },
+        }
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        match self.current {
+            Some((ref a, ref b)) if point == a || point == b => Some(Highlight::Current),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Maze generation algorithms. Each one implements [`MazeGenerator`] and is driven one step at a
+//! time by [`GridState`](crate::maze::GridState).
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::XY;
+use crate::solve;
+use crate::maze::{CellGrid, EdgeState, GenParams, GridCellKind, GridState};
+
+mod aldous_broder;
+mod backtracker;
+mod binary_tree;
+pub(crate) mod disjoint_set;
+mod eller;
+mod growing_tree;
+mod hunt_and_kill;
+mod kruskal;
+mod path_fill;
+mod prim;
+mod recursive_division;
+mod sidewinder;
+mod wilson;
+
+pub use aldous_broder::AldousBroderGenerator;
+pub use backtracker::BacktrackerGenerator;
+pub use binary_tree::BinaryTreeGenerator;
+pub use eller::{eller_rows, EllerGenerator, EllerIter, EllerRows};
+pub use growing_tree::{CellPick, GrowingTreeGenerator, GrowingTreePolicy};
+pub use hunt_and_kill::HuntAndKillGenerator;
+pub use kruskal::KruskalGenerator;
+pub use path_fill::{GenStage, PathFillGenerator};
+pub use prim::PrimGenerator;
+pub use recursive_division::RecursiveDivisionGenerator;
+pub use sidewinder::SidewinderGenerator;
+pub use wilson::WilsonGenerator;
+
+/// What a generator wants to happen after a step.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepResult {
+    /// Call `step` again.
+    Continue,
+
+    /// A milestone was reached. When generation is being animated, wait this many milliseconds
+    /// before the next step so it can be seen.
+    Pause(u64),
+
+    /// The maze is finished.
+    Done,
+}
+
+/// How a cell should be highlighted while a generator is working, so the viewer can show its
+/// progress.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Highlight {
+    /// The cell the generator is working on right now.
+    Current,
+
+    /// A cell the generator is considering next.
+    Frontier,
+
+    /// A cell on a random walk that hasn't been added to the maze yet.
+    Walk,
+
+    /// A cell the generator is scanning.
+    Scan,
+}
+
+/// The corner that biased generators like binary tree and sidewinder lean towards. Every cell
+/// has a route towards this corner, which gives the maze a diagonal texture.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Bias {
+    #[default]
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl Bias {
+    fn is_north(self) -> bool {
+        matches!(self, Bias::NorthEast | Bias::NorthWest)
+    }
+
+    fn is_east(self) -> bool {
+        matches!(self, Bias::NorthEast | Bias::SouthEast)
+    }
+
+    /// The neighbor above or below the given cell in the direction of the bias, if it's in the maze.
+    pub(crate) fn vertical_neighbor(self, grid: &CellGrid, point: &XY) -> Option<XY> {
+        let XY(x, y) = *point;
+        if self.is_north() {
+            if y < grid.height() - 2 { Some(XY(x, y + 1)) } else { None }
+        }
+        else if y > 0 {
+            Some(XY(x, y - 1))
+        }
+        else {
+            None
+        }
+    }
+
+    /// The neighbor to the left or right of the given cell in the direction of the bias, if it's
+    /// in the maze.
+    pub(crate) fn horizontal_neighbor(self, grid: &CellGrid, point: &XY) -> Option<XY> {
+        let XY(x, y) = *point;
+        if self.is_east() {
+            if x < grid.width() - 2 { Some(XY(x + 1, y)) } else { None }
+        }
+        else if x > 0 {
+            Some(XY(x - 1, y))
+        }
+        else {
+            None
+        }
+    }
+
+    /// The maze cell at `index` when visiting cells a row at a time, moving towards the bias
+    /// horizontally within each row.
+    pub(crate) fn scan_cell(self, grid: &CellGrid, index: usize) -> XY {
+        let row_len = grid.width() - 1;
+        let x = index % row_len;
+        let y = index / row_len;
+        if self.is_east() {
+            XY(x, y)
+        }
+        else {
+            XY(row_len - 1 - x, y)
+        }
+    }
+}
+
+impl fmt::Display for Bias {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Bias::NorthEast => "ne",
+            Bias::NorthWest => "nw",
+            Bias::SouthEast => "se",
+            Bias::SouthWest => "sw",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Bias {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ne" => Ok(Bias::NorthEast),
+            "nw" => Ok(Bias::NorthWest),
+            "se" => Ok(Bias::SouthEast),
+            "sw" => Ok(Bias::SouthWest),
+            _ => Err(format!("unknown bias '{}'", s)),
+        }
+    }
+}
+
+/// A maze generation algorithm that works on a [`CellGrid`] one step at a time.
+///
+/// The grid starts with every cell blank. Generators are expected to turn on the border walls,
+/// open an entrance and exit, and tag the cells between them as the path, so that the rest of the
+/// crate can treat every maze the same way.
+pub trait MazeGenerator {
+    /// The name used to pick this generator at runtime.
+    fn name(&self) -> &'static str;
+
+    /// Does one unit of work on the grid.
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult;
+
+    /// How the given cell should be highlighted, if at all.
+    fn highlight(&self, _point: &XY) -> Option<Highlight> {
+        None
+    }
+
+    /// A short description of how much work generation took, for comparing algorithms.
+    fn summary(&self) -> Option<String> {
+        None
+    }
+}
+
+/// The names of every available generator, in the order the viewer cycles through them.
+pub const GENERATOR_NAMES: &[&str] = &[
+    "path-fill",
+    "backtracker",
+    "kruskal",
+    "wilson",
+    "prim",
+    "aldous-broder",
+    "aldous-broder-wilson",
+    "eller",
+    "growing-tree",
+    "hunt-and-kill",
+    "binary-tree",
+    "sidewinder",
+    "recursive-division",
+];
+
+/// Creates a generator by name, or returns `None` if there's no generator with that name.
+pub fn create(name: &str, params: &GenParams) -> Option<Box<dyn MazeGenerator>> {
+    match name {
+        "path-fill" => Some(Box::new(PathFillGenerator::new(params))),
+        "backtracker" => Some(Box::new(BacktrackerGenerator::new())),
+        "kruskal" => Some(Box::new(KruskalGenerator::new())),
+        "wilson" => Some(Box::new(WilsonGenerator::new())),
+        "prim" => Some(Box::new(PrimGenerator::new())),
+        "aldous-broder" => Some(Box::new(AldousBroderGenerator::new())),
+        "aldous-broder-wilson" => Some(Box::new(AldousBroderGenerator::hybrid(params.hybrid_switch_fraction))),
+        "eller" => Some(Box::new(EllerGenerator::new())),
+        "growing-tree" => Some(Box::new(GrowingTreeGenerator::new(params.growing_tree_policy.clone()))),
+        "hunt-and-kill" => Some(Box::new(HuntAndKillGenerator::new())),
+        "binary-tree" => Some(Box::new(BinaryTreeGenerator::new(params.bias))),
+        "sidewinder" => Some(Box::new(SidewinderGenerator::new(params.bias))),
+        "recursive-division" => Some(Box::new(RecursiveDivisionGenerator::new(params.room_size))),
+        _ => None,
+    }
+}
+
+/// Picks a cell inside the maze uniformly at random.
+pub(crate) fn random_maze_cell(rng: &mut StdRng, grid: &CellGrid) -> XY {
+    XY(rng.gen_range(0, grid.width() - 1), rng.gen_range(0, grid.height() - 1))
+}
+
+/// Turns on the border and every wall between cells, so that a generator can carve passages out
+/// of solid walls.
+pub(crate) fn fill_all_walls(grid: &mut CellGrid) {
+    GridState::fill_borders(grid);
+
+    let width = grid.width();
+    let height = grid.height();
+    for (y, row) in grid.chunks_mut(width).enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            if x < width - 1 && y < height - 1 {
+                cell.left_edge = EdgeState::On;
+                cell.bottom_edge = EdgeState::On;
+            }
+            else if y < height - 1 {
+                cell.left_edge = EdgeState::On;
+            }
+            else if x < width - 1 {
+                cell.bottom_edge = EdgeState::On;
+            }
+        }
+    }
+}
+
+/// The cells inside the maze that share a side with the given cell.
+pub(crate) fn maze_neighbors(grid: &CellGrid, point: &XY) -> Vec<XY> {
+    let XY(x, y) = *point;
+    let mut neighbors = Vec::with_capacity(4);
+
+    if x > 0 {
+        neighbors.push(XY(x - 1, y));
+    }
+
+    if y > 0 {
+        neighbors.push(XY(x, y - 1));
+    }
+
+    if x < grid.width() - 2 {
+        neighbors.push(XY(x + 1, y));
+    }
+
+    if y < grid.height() - 2 {
+        neighbors.push(XY(x, y + 1));
+    }
+
+    neighbors
+}
+
+/// The edge between two neighboring cells is owned by whichever of them is further right or up.
+fn shared_edge<'a>(grid: &'a mut CellGrid, a: &XY, b: &XY) -> &'a mut EdgeState {
+    if a.1 == b.1 {
+        let right = if a.0 > b.0 { a } else { b };
+        &mut grid[right].left_edge
+    }
+    else {
+        let above = if a.1 > b.1 { a } else { b };
+        &mut grid[above].bottom_edge
+    }
+}
+
+/// Sets the state of the wall between two neighboring cells.
+pub(crate) fn set_wall_between(grid: &mut CellGrid, a: &XY, b: &XY, state: EdgeState) {
+    *shared_edge(grid, a, b) = state;
+}
+
+/// Whether there's a wall between two neighboring cells.
+pub(crate) fn has_wall_between(grid: &CellGrid, a: &XY, b: &XY) -> bool {
+    if a.1 == b.1 {
+        grid[if a.0 > b.0 { a } else { b }].has_left_edge()
+    }
+    else {
+        grid[if a.1 > b.1 { a } else { b }].has_bottom_edge()
+    }
+}
+
+/// Picks a random cell on the border of the maze.
+pub(crate) fn random_border_cell(rng: &mut StdRng, grid: &CellGrid) -> XY {
+    loop {
+        let point = random_maze_cell(rng, grid);
+        if GridState::is_valid_start_or_end(grid, &point) {
+            return point;
+        }
+    }
+}
+
+/// Opens the border walls at the given start and end cells and tags the route between them as the
+/// path, the same way the path-fill generator does.
+pub(crate) fn open_entrance_and_exit(rng: &mut StdRng, grid: &mut CellGrid, start: &XY, end: &XY) {
+    GridState::erase_start_or_end_edge(rng, grid, start);
+    GridState::erase_start_or_end_edge(rng, grid, end);
+
+    if let Some(route) = solve::shortest_path(grid, start, end) {
+        let len = route.len();
+        for (i, point) in route.iter().enumerate() {
+            grid[point].kind = if i == len - 1 { GridCellKind::End } else { GridCellKind::Path(i) };
+        }
+    }
+}
+
+/// Opens an entrance and exit at two different random cells on the border.
+pub(crate) fn open_random_entrance_and_exit(rng: &mut StdRng, grid: &mut CellGrid) {
+    let start = random_border_cell(rng, grid);
+    let mut end = random_border_cell(rng, grid);
+    while end == start {
+        end = random_border_cell(rng, grid);
+    }
+
+    open_entrance_and_exit(rng, grid, &start, &end);
+}
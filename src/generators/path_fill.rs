@@ -0,0 +1,570 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState, GenParams, GridCellKind, GridState, MazeReport};
+use super::{maze_neighbors, set_wall_between, MazeGenerator, StepResult};
+
+// How many times to roll the walls randomly before fixing up the last roll instead.
+const MAX_EDGE_ROLLS: usize = 1000;
+
+/// The stages that maze generation goes through, in order.
+#[derive(Clone, Debug)]
+pub enum GenStage {
+    Borders,
+    Path,
+    EnableEdgesRandomly(usize),
+    EraseRandomInvalidEdges(usize),
+    EraseInvalidEdges(usize),
+    Rest,
+    Validate,
+    Done,
+}
+
+#[derive(Clone, Debug)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Clone)]
+struct PathPoint {
+    point: XY,
+    dir: Direction,
+}
+
+#[derive(Clone)]
+struct CellEdge {
+    point: XY,
+    is_left_edge: bool,
+}
+
+/// Lays down a winding path between two border cells, fills in walls
+/// randomly around it, then breaks up any sections that got enclosed.
+pub struct PathFillGenerator {
+    stage: GenStage,
+    params: GenParams,
+
+    // How many steps were spent erasing edges to break up enclosures.
+    erase_steps: usize,
+
+    // How many walls were changed to fix problems found by validation.
+    validation_fixes: usize,
+
+    // What was still wrong with the maze when validation gave up, if it did.
+    unfixed: Option<MazeReport>,
+}
+
+impl PathFillGenerator {
+    pub fn new(params: &GenParams) -> PathFillGenerator {
+        PathFillGenerator {
+            stage: GenStage::Borders,
+            params: params.clone(),
+            erase_steps: 0,
+            validation_fixes: 0,
+            unfixed: None,
+        }
+    }
+
+    /// The stage that generation is currently in.
+    pub fn stage(&self) -> &GenStage {
+        &self.stage
+    }
+
+    fn set_stage(&mut self, stage: GenStage) -> StepResult {
+        self.stage = stage;
+        StepResult::Continue
+    }
+
+    fn set_stage_delayed(&mut self, stage: GenStage, millis: u64) -> StepResult {
+        self.set_stage(stage);
+        StepResult::Pause(millis)
+    }
+
+    fn fill_borders(&mut self, grid: &mut CellGrid) -> StepResult {
+        GridState::fill_borders(grid);
+        self.set_stage_delayed(GenStage::Path, 1000)
+    }
+
+    fn update_path(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let width = grid.width();
+        let height = grid.height();
+
+        let mut path = Vec::<PathPoint>::new();
+
+        // For now choose the start point in a corner
+        let start = {
+            let start_bottom = rng.gen_bool(0.5);
+            let start_left = rng.gen_bool(0.5);
+            let start_points_vertical = rng.gen_bool(0.5);
+
+            PathPoint {
+                point: XY(if start_left { 0 } else { width - 2 }, if start_bottom { 0 } else { height - 2 }),
+                dir: if start_points_vertical {
+                         if start_bottom {
+                             Direction::Up
+                         }
+                         else {
+                             Direction::Down
+                         }
+                     }
+                     else {
+                         if start_left {
+                             Direction::Right
+                         }
+                         else {
+                             Direction::Left
+                         }
+                     },
+            }
+        };
+
+        path.push(start.clone());
+
+        while path.len() < self.params.path_point_count {
+            loop {
+                let start_left = start.point.0 == 0;
+                let start_bottom = start.point.1 == 0;
+                let last = &path.last().unwrap();
+
+                // Alternate directions. respect whether moving from left to right and up to down
+                // or the other way.
+                let dir =
+                    match last.dir {
+                        Direction::Up | Direction::Down => {
+                            if start_left {
+                                Direction::Right
+                            }
+                            else {
+                                Direction::Left
+                            }
+                        },
+                        Direction::Left | Direction::Right => {
+                            if start_bottom {
+                                Direction::Up
+                            }
+                            else {
+                                Direction::Down
+                            }
+                        },
+                    };
+
+                let mut should_reset_path = false;
+
+                // The point about to be added will need to move in the next direction afterwards,
+                // unless it's the end of the path.
+                if path.len() < self.params.path_point_count - 1 {
+                    match dir {
+                        Direction::Up if last.point.1 == height - 2 => should_reset_path = true,
+                        Direction::Down if last.point.1 == 0 => should_reset_path = true,
+                        Direction::Left if last.point.0 == 0 => should_reset_path = true,
+                        Direction::Right if last.point.0 == width - 2 => should_reset_path = true,
+                        _ => ()
+                    };
+                }
+
+                // Every later move along the same axis needs at least one cell to travel through, so
+                // leave room for them. Otherwise the path can run into the far side too early and keep
+                // resetting forever.
+                let reserved = (self.params.path_point_count - path.len() - 1) / 2;
+                let has_room =
+                    match last.dir {
+                        Direction::Up => last.point.1 < height - 1 - reserved,
+                        Direction::Down => reserved < last.point.1,
+                        Direction::Left => reserved < last.point.0,
+                        Direction::Right => last.point.0 < width - 1 - reserved,
+                    };
+
+                if !has_room {
+                    should_reset_path = true;
+                }
+
+                if !should_reset_path {
+                    let point =
+                        match last.dir {
+                            Direction::Up => XY(last.point.0, rng.gen_range(last.point.1, height - 1 - reserved)),
+                            Direction::Down => XY(last.point.0, rng.gen_range(reserved, last.point.1)),
+                            Direction::Left => XY(rng.gen_range(reserved, last.point.0), last.point.1),
+                            Direction::Right => XY(rng.gen_range(last.point.0, width - 1 - reserved), last.point.1),
+                        };
+
+                    let is_valid =
+                        path.iter().find(|&item| { item.point == point }).is_none() &&
+                        ((path.len() != self.params.path_point_count - 1) || GridState::is_valid_start_or_end(grid, &point));
+
+                    if is_valid {
+                        path.push(PathPoint { point, dir });
+                        break;
+                    }
+                    else if path.len() == self.params.path_point_count - 1 {
+                        should_reset_path = true;
+                    }
+                }
+
+                if should_reset_path {
+                    path.clear();
+                    path.push(start.clone());
+                }
+            }
+        }
+
+        // if cornered (can't even travel one step in any direction), then restart from the start point
+        // pick a random direction and random distance
+        //    but if this is the last point, then the distance must extend all the way to a wall
+        // check if point is valid on the path
+        // add point to path
+
+        // Now set the path value on every cell in the chosen path.
+        let len = path.len();
+        for (i, point) in path.iter().enumerate() {
+            match i {
+                0 => {
+                    grid[&point.point].kind = GridCellKind::Path(i);
+                    GridState::erase_start_or_end_edge(rng, grid, &point.point);
+                },
+                _ if i == len - 1 => {
+                    grid[&point.point].kind = GridCellKind::End;
+                    GridState::erase_start_or_end_edge(rng, grid, &point.point);
+                },
+                _ => {
+                    grid[&point.point].kind = GridCellKind::Path(i);
+                },
+            };
+        }
+
+        // Walk the path and erase edges to ensure the path is open.
+        path.iter().fold(None, |last_opt : Option<&PathPoint>, step| {
+            if let Some(last) = last_opt {
+                let XY(mut x, mut y) = last.point;
+                while x != step.point.0 || y != step.point.1 {
+                    match last.dir {
+                        Direction::Up => {
+                            let cell = &mut grid[XY(x, y+1)];
+                            cell.bottom_edge = EdgeState::Off;
+                            if let GridCellKind::Empty = cell.kind {
+                                cell.kind = GridCellKind::PathIntermediate;
+                            }
+                            y += 1;
+                        },
+                        Direction::Down => {
+                            let cell = &mut grid[XY(x, y)];
+                            cell.bottom_edge = EdgeState::Off;
+                            if let GridCellKind::Empty = cell.kind {
+                                cell.kind = GridCellKind::PathIntermediate;
+                            }
+                            y -= 1;
+                        },
+                        Direction::Left => {
+                            let cell = &mut grid[XY(x, y)];
+                            cell.left_edge = EdgeState::Off;
+                            if let GridCellKind::Empty = cell.kind {
+                                cell.kind = GridCellKind::PathIntermediate;
+                            }
+                            x -= 1;
+                        },
+                        Direction::Right => {
+                            let cell = &mut grid[XY(x+1, y)];
+                            cell.left_edge = EdgeState::Off;
+                            if let GridCellKind::Empty = cell.kind {
+                                cell.kind = GridCellKind::PathIntermediate;
+                            }
+                            x += 1;
+                        },
+                    }
+                }
+            }
+
+            Some(step)
+        });
+
+        self.set_stage_delayed(GenStage::EnableEdgesRandomly(0), 1000)
+    }
+
+    fn enable_edges_randomly(&mut self, rng: &mut StdRng, grid: &mut CellGrid, attempt: usize) -> StepResult {
+        // Reset all provisionally-on edges to unset to try again.
+        for cell in grid.iter_mut() {
+            if let EdgeState::ProvisionallyOn = cell.bottom_edge {
+                cell.bottom_edge = EdgeState::Unset;
+            }
+
+            if let EdgeState::ProvisionallyOn = cell.left_edge {
+                cell.left_edge = EdgeState::Unset;
+            }
+        }
+
+        // Turn on edges randomly
+        for cell in grid.iter_mut() {
+            if let EdgeState::Unset = cell.bottom_edge {
+                if rng.gen_bool(self.params.edge_enabled_chance) {
+                    cell.bottom_edge = EdgeState::ProvisionallyOn;
+                }
+            }
+
+            if let EdgeState::Unset = cell.left_edge {
+                if rng.gen_bool(self.params.edge_enabled_chance) {
+                    cell.left_edge = EdgeState::ProvisionallyOn;
+                }
+            }
+        }
+
+        if GridState::has_inner_grid_walls(grid) {
+            return self.set_stage_delayed(GenStage::EraseRandomInvalidEdges(0), 250);
+        }
+
+        // With a low edge chance or a big grid, a roll where every 2x2 sub-grid has an inner wall
+        // might never come up, so after enough tries just put a wall into the ones without.
+        if attempt + 1 >= MAX_EDGE_ROLLS {
+            Self::close_open_sub_grids(rng, grid);
+            return self.set_stage_delayed(GenStage::EraseRandomInvalidEdges(0), 250);
+        }
+
+        self.set_stage(GenStage::EnableEdgesRandomly(attempt + 1))
+    }
+
+    // Turns on one of the unset inner walls of every 2x2 sub-grid that has none, so that the grid
+    // passes GridState::has_inner_grid_walls. The path can open at most three of the four inner
+    // walls without looping back on itself, so there's always one left to turn on.
+    fn close_open_sub_grids(rng: &mut StdRng, grid: &mut CellGrid) {
+        for y in 0 .. grid.height() - 1 {
+            for x in 0 .. grid.width() - 1 {
+                let inner_edges = [
+                    CellEdge { point: XY(x + 1, y), is_left_edge: true },
+                    CellEdge { point: XY(x, y + 1), is_left_edge: false },
+                    CellEdge { point: XY(x + 1, y + 1), is_left_edge: true },
+                    CellEdge { point: XY(x + 1, y + 1), is_left_edge: false },
+                ];
+
+                let is_on = |edge: &CellEdge| {
+                    let cell = &grid[&edge.point];
+                    if edge.is_left_edge { cell.has_left_edge() } else { cell.has_bottom_edge() }
+                };
+
+                if inner_edges.iter().any(is_on) {
+                    continue;
+                }
+
+                let unset: Vec<&CellEdge> = inner_edges.iter().filter(|edge| {
+                    let cell = &grid[&edge.point];
+                    matches!(if edge.is_left_edge { &cell.left_edge } else { &cell.bottom_edge }, EdgeState::Unset)
+                }).collect();
+
+                if unset.is_empty() {
+                    continue;
+                }
+
+                let edge = unset[rng.gen_range(0, unset.len())];
+                if edge.is_left_edge {
+                    grid[&edge.point].left_edge = EdgeState::ProvisionallyOn;
+                }
+                else {
+                    grid[&edge.point].bottom_edge = EdgeState::ProvisionallyOn;
+                }
+            }
+        }
+    }
+
+    fn erase_edge_in_enclosure(rng: &mut StdRng, grid: &mut CellGrid, point: &XY) -> bool {
+        if GridState::find_enclosed_section(grid, point).is_some() {
+            let mut tries = 4;
+            while let Some(edge_to_erase) = Self::pick_random_non_border_edge(rng, grid, point) {
+                if tries == 0 {
+                    return false;
+                }
+
+                let cell_orig = grid[edge_to_erase.point.clone()].clone();
+
+                Self::erase_cell_edge(grid, &edge_to_erase);
+
+                if !GridState::has_inner_grid_walls(grid) {
+                    tries -= 1;
+                    grid[edge_to_erase.point.clone()] = cell_orig;
+                }
+                else {
+                    return true;
+                }
+            }
+        }
+
+        // Nothing more to do with this point: no enclosure found.
+        false
+    }
+
+    fn erase_random_invalid_edge(&mut self, rng: &mut StdRng, grid: &mut CellGrid, iteration: usize) -> StepResult {
+        if iteration < grid.len() / self.params.random_invalid_edge_erase_factor {
+            loop {
+                let point = grid.index_to_xy(rng.gen_range(0, grid.len()));
+                if point.0 < grid.width() - 1 && point.1 < grid.height() - 1 {
+                    Self::erase_edge_in_enclosure(rng, grid, &point);
+                    return self.set_stage(GenStage::EraseRandomInvalidEdges(iteration + 1));
+                }
+            }
+        }
+        else {
+            self.set_stage(GenStage::EraseInvalidEdges(0))
+        }
+    }
+
+    fn erase_invalid_edge(&mut self, rng: &mut StdRng, grid: &mut CellGrid, starting_index: usize) -> StepResult {
+        for i in starting_index .. grid.len() {
+            let point = grid.index_to_xy(i);
+            if point.0 < grid.width() - 1 && point.1 < grid.height() - 1 {
+                if !Self::erase_edge_in_enclosure(rng, grid, &point) {
+                    return self.set_stage(GenStage::EraseInvalidEdges(i + 1));
+                }
+
+                return StepResult::Continue;
+            }
+        }
+
+        self.set_stage_delayed(GenStage::Rest, 500)
+    }
+
+    fn fill_rest_of_maze(&mut self, grid: &mut CellGrid) -> StepResult {
+        // Make all the provisional edges real
+        for cell in grid.iter_mut() {
+            if let EdgeState::ProvisionallyOn = cell.bottom_edge {
+                cell.bottom_edge = EdgeState::On;
+            }
+
+            if let EdgeState::ProvisionallyOn = cell.left_edge {
+                cell.left_edge = EdgeState::On;
+            }
+        }
+
+        if self.params.strict && !GridState::has_valid_edges(grid) {
+            return self.set_stage_delayed(GenStage::Validate, 250);
+        }
+
+        self.set_stage(GenStage::Done);
+        StepResult::Done
+    }
+
+    // Fixes one problem found by validation per step, until there are none left.
+    fn fix_invalid_maze(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let report = GridState::check_maze(grid);
+        if report.is_clean() {
+            self.set_stage(GenStage::Done);
+            return StepResult::Done;
+        }
+
+        if !report.unreachable_cells.is_empty() {
+            // Join an unreachable cell onto a reachable neighbor, which can't make a loop.
+            let mut is_unreachable = Grid::new(grid.width(), grid.height(), &false);
+            for point in report.unreachable_cells.iter() {
+                is_unreachable[point] = true;
+            }
+
+            let mut joins = Vec::new();
+            for point in report.unreachable_cells.iter() {
+                for neighbor in maze_neighbors(grid, point) {
+                    if !is_unreachable[&neighbor] && !matches!(grid[&neighbor].kind, GridCellKind::Removed) {
+                        joins.push((point.clone(), neighbor));
+                    }
+                }
+            }
+
+            if !joins.is_empty() {
+                let (a, b) = &joins[rng.gen_range(0, joins.len())];
+                set_wall_between(grid, a, b, EdgeState::Off);
+                self.validation_fixes += 1;
+                return StepResult::Continue;
+            }
+        }
+        else if !report.loops.is_empty() {
+            // Putting back any wall that closes a loop keeps everything connected.
+            let (a, b) = &report.loops[rng.gen_range(0, report.loops.len())];
+            set_wall_between(grid, a, b, EdgeState::On);
+            self.validation_fixes += 1;
+            return StepResult::Continue;
+        }
+
+        // Only the start or end are wrong, and moving them would mean starting over.
+        self.unfixed = Some(report);
+        self.set_stage(GenStage::Done);
+        StepResult::Done
+    }
+
+    fn erase_cell_edge(grid: &mut CellGrid, edge_to_erase: &CellEdge) {
+        if edge_to_erase.is_left_edge {
+            grid[&edge_to_erase.point].left_edge = EdgeState::Unset;
+        }
+        else {
+            grid[&edge_to_erase.point].bottom_edge = EdgeState::Unset;
+        }
+    }
+
+    fn pick_random_non_border_edge(rng: &mut StdRng, grid: &CellGrid, point: &XY) -> Option<CellEdge> {
+        let mut edges = [None, None, None, None];
+        let mut edge_count = 0;
+
+        let cell = &grid[point];
+        let adjacent_right_point = XY(point.0 + 1, point.1);
+        let adjacent_above_point = XY(point.0, point.1 + 1);
+
+        if point.0 > 0 && cell.has_left_edge() {
+            edges[edge_count] = Some(CellEdge { point: point.clone(), is_left_edge: true });
+            edge_count += 1;
+        }
+
+        if point.1 > 0 && cell.has_bottom_edge() {
+            edges[edge_count] = Some(CellEdge { point: point.clone(), is_left_edge: false });
+            edge_count += 1;
+        }
+
+        if point.0 < grid.width() - 2 && grid[&adjacent_right_point].has_left_edge() {
+            edges[edge_count] = Some(CellEdge { point: adjacent_right_point.clone(), is_left_edge: true });
+            edge_count += 1;
+        }
+
+        if point.1 < grid.height() - 2 && grid[&adjacent_above_point].has_bottom_edge() {
+            edges[edge_count] = Some(CellEdge { point: adjacent_above_point.clone(), is_left_edge: false });
+            edge_count += 1;
+        }
+
+        if edge_count > 0 {
+            Some(edges[rng.gen_range(0, edge_count)].as_ref().unwrap().clone())
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl MazeGenerator for PathFillGenerator {
+    fn name(&self) -> &'static str {
+        "path-fill"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        match self.stage {
+            GenStage::Borders => self.fill_borders(grid),
+            GenStage::Path => self.update_path(rng, grid),
+            GenStage::EnableEdgesRandomly(attempt) => self.enable_edges_randomly(rng, grid, attempt),
+            GenStage::EraseRandomInvalidEdges(iteration) => {
+                self.erase_steps += 1;
+                self.erase_random_invalid_edge(rng, grid, iteration)
+            },
+            GenStage::EraseInvalidEdges(starting_index) => {
+                self.erase_steps += 1;
+                self.erase_invalid_edge(rng, grid, starting_index)
+            },
+            GenStage::Rest => self.fill_rest_of_maze(grid),
+            GenStage::Validate => self.fix_invalid_maze(rng, grid),
+            GenStage::Done => StepResult::Done,
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        if let Some(ref report) = self.unfixed {
+            Some(format!("{} edge erase steps, {} validation fixes, could not be fixed: {}", self.erase_steps, self.validation_fixes, report))
+        }
+        else if self.params.strict {
+            Some(format!("{} edge erase steps, {} validation fixes", self.erase_steps, self.validation_fixes))
+        }
+        else {
+            Some(format!("{} edge erase steps", self.erase_steps))
+        }
+    }
+}
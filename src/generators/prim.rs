@@ -0,0 +1,114 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    fill_all_walls,
+    maze_neighbors,
+    open_random_entrance_and_exit,
+    random_maze_cell,
+    set_wall_between,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// Grows a perfect maze outward from one cell with randomized Prim's algorithm. Each step picks a
+/// random wall between the maze and a cell outside it, and knocks it down to pull that cell in.
+pub struct PrimGenerator {
+    state: Option<FrontierState>,
+}
+
+struct FrontierState {
+    in_maze: Grid<bool>,
+    is_frontier: Grid<bool>,
+
+    // Walls that might lead out of the maze, as (cell in the maze, cell outside it).
+    frontier_edges: Vec<(XY, XY)>,
+    last_added: Option<XY>,
+}
+
+impl PrimGenerator {
+    pub fn new() -> PrimGenerator {
+        PrimGenerator {
+            state: None,
+        }
+    }
+}
+
+impl Default for PrimGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrontierState {
+    fn add_to_maze(&mut self, grid: &CellGrid, point: &XY) {
+        self.in_maze[point] = true;
+        self.is_frontier[point] = false;
+        self.last_added = Some(point.clone());
+
+        for neighbor in maze_neighbors(grid, point) {
+            if !self.in_maze[&neighbor] {
+                self.is_frontier[&neighbor] = true;
+                self.frontier_edges.push((point.clone(), neighbor));
+            }
+        }
+    }
+}
+
+impl MazeGenerator for PrimGenerator {
+    fn name(&self) -> &'static str {
+        "prim"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let state = match self.state {
+            Some(ref mut state) => state,
+            None => {
+                fill_all_walls(grid);
+
+                let mut state = FrontierState {
+                    in_maze: Grid::new(grid.width(), grid.height(), &false),
+                    is_frontier: Grid::new(grid.width(), grid.height(), &false),
+                    frontier_edges: Vec::new(),
+                    last_added: None,
+                };
+
+                let start = random_maze_cell(rng, grid);
+                state.add_to_maze(grid, &start);
+                self.state = Some(state);
+                return StepResult::Pause(250);
+            },
+        };
+
+        // Skip over walls whose far side already joined the maze through some other wall, so that
+        // every step adds one cell.
+        while !state.frontier_edges.is_empty() {
+            let (from, to) = state.frontier_edges.swap_remove(rng.gen_range(0, state.frontier_edges.len()));
+            if !state.in_maze[&to] {
+                set_wall_between(grid, &from, &to, EdgeState::Off);
+                state.add_to_maze(grid, &to);
+                return StepResult::Continue;
+            }
+        }
+
+        state.last_added = None;
+        open_random_entrance_and_exit(rng, grid);
+        StepResult::Done
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        let state = self.state.as_ref()?;
+        if state.last_added.as_ref() == Some(point) {
+            Some(Highlight::Current)
+        }
+        else if state.is_frontier[point] {
+            Some(Highlight::Frontier)
+        }
+        else {
+            None
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::XY;
+use crate::maze::{CellGrid, EdgeState, GridState};
+use super::{
+    open_random_entrance_and_exit,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// A rectangle of maze cells with no walls inside it yet.
+#[derive(Clone, Debug)]
+struct Chamber {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Chamber {
+    fn contains(&self, point: &XY) -> bool {
+        (self.x .. self.x + self.width).contains(&point.0) && (self.y .. self.y + self.height).contains(&point.1)
+    }
+}
+
+/// Makes a maze by adding walls instead of carving them. It starts with only the border walls and
+/// splits the open space in two with a wall that has one gap in it, then does the same to each
+/// half until the chambers are too small to split.
+///
+/// Chambers whose width and height are both at most the room size are left open as rooms. With a
+/// room size of 1, every chamber is split down to single cells, which makes a perfect maze.
+pub struct RecursiveDivisionGenerator {
+    room_size: usize,
+    chambers: Option<Vec<Chamber>>,
+    last_split: Option<Chamber>,
+}
+
+impl RecursiveDivisionGenerator {
+    pub fn new(room_size: usize) -> RecursiveDivisionGenerator {
+        RecursiveDivisionGenerator {
+            room_size,
+            chambers: None,
+            last_split: None,
+        }
+    }
+}
+
+impl Default for RecursiveDivisionGenerator {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl MazeGenerator for RecursiveDivisionGenerator {
+    fn name(&self) -> &'static str {
+        "recursive-division"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let chambers = match self.chambers {
+            Some(ref mut chambers) => chambers,
+            None => {
+                // First step: just the borders, with everything inside open.
+                GridState::fill_borders(grid);
+                let width = grid.width();
+                let height = grid.height();
+                for (y, row) in grid.chunks_mut(width).enumerate() {
+                    for (x, cell) in row.iter_mut().enumerate() {
+                        if x > 0 && x < width - 1 && y < height - 1 {
+                            cell.left_edge = EdgeState::Off;
+                        }
+
+                        if y > 0 && y < height - 1 && x < width - 1 {
+                            cell.bottom_edge = EdgeState::Off;
+                        }
+                    }
+                }
+
+                self.chambers = Some(vec![Chamber { x: 0, y: 0, width: width - 1, height: height - 1 }]);
+                return StepResult::Pause(250);
+            },
+        };
+
+        // Skip over chambers that won't be split, so that every step adds a wall.
+        while let Some(chamber) = chambers.pop() {
+            let can_split_vertically = chamber.width >= 2;
+            let can_split_horizontally = chamber.height >= 2;
+            if (!can_split_vertically && !can_split_horizontally) ||
+               (chamber.width <= self.room_size && chamber.height <= self.room_size) {
+                continue;
+            }
+
+            // Split across the longer side so chambers don't end up long and thin.
+            let split_horizontally =
+                if !can_split_vertically {
+                    true
+                }
+                else if !can_split_horizontally {
+                    false
+                }
+                else if chamber.width == chamber.height {
+                    rng.gen_bool(0.5)
+                }
+                else {
+                    chamber.height > chamber.width
+                };
+
+            if split_horizontally {
+                // The wall runs along the bottom edges of row `wall_y`.
+                let wall_y = rng.gen_range(chamber.y + 1, chamber.y + chamber.height);
+                let gap_x = rng.gen_range(chamber.x, chamber.x + chamber.width);
+                for x in chamber.x .. chamber.x + chamber.width {
+                    if x != gap_x {
+                        grid[XY(x, wall_y)].bottom_edge = EdgeState::On;
+                    }
+                }
+
+                chambers.push(Chamber { x: chamber.x, y: chamber.y, width: chamber.width, height: wall_y - chamber.y });
+                chambers.push(Chamber { x: chamber.x, y: wall_y, width: chamber.width, height: chamber.y + chamber.height - wall_y });
+            }
+            else {
+                // The wall runs along the left edges of column `wall_x`.
+                let wall_x = rng.gen_range(chamber.x + 1, chamber.x + chamber.width);
+                let gap_y = rng.gen_range(chamber.y, chamber.y + chamber.height);
+                for y in chamber.y .. chamber.y + chamber.height {
+                    if y != gap_y {
+                        grid[XY(wall_x, y)].left_edge = EdgeState::On;
+                    }
+                }
+
+                chambers.push(Chamber { x: chamber.x, y: chamber.y, width: wall_x - chamber.x, height: chamber.height });
+                chambers.push(Chamber { x: wall_x, y: chamber.y, width: chamber.x + chamber.width - wall_x, height: chamber.height });
+            }
+
+            self.last_split = Some(chamber);
+            return StepResult::Continue;
+        }
+
+        self.last_split = None;
+        open_random_entrance_and_exit(rng, grid);
+        StepResult::Done
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        match self.last_split {
+            Some(ref chamber) if chamber.contains(point) => Some(Highlight::Frontier),
+            _ => None,
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        Some(format!("room size {}", self.room_size))
+    }
+}
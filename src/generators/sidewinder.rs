@@ -0,0 +1,131 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    fill_all_walls,
+    open_random_entrance_and_exit,
+    set_wall_between,
+    Bias,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+const CLOSE_RUN_CHANCE: f64 = 0.5;
+
+/// Makes a perfect maze with the sidewinder algorithm. Each row is carved into horizontal runs of
+/// cells, and every run gets one opening towards the vertical direction of the bias. The row
+/// furthest in that direction is one long corridor.
+pub struct SidewinderGenerator {
+    bias: Bias,
+
+    // How many cells have been visited, in the order given by `Bias::scan_cell`.
+    next_index: Option<usize>,
+
+    // The cells in the current row that are joined together but not yet to the next row.
+    run: Vec<XY>,
+
+    // Which cells are in the current run, so highlighting doesn't have to search it.
+    in_run: Option<Grid<bool>>,
+}
+
+impl SidewinderGenerator {
+    pub fn new(bias: Bias) -> SidewinderGenerator {
+        SidewinderGenerator {
+            bias,
+            next_index: None,
+            run: Vec::new(),
+            in_run: None,
+        }
+    }
+
+    fn clear_run(&mut self) {
+        if let Some(ref mut in_run) = self.in_run {
+            for point in self.run.iter() {
+                in_run[point] = false;
+            }
+        }
+
+        self.run.clear();
+    }
+}
+
+impl Default for SidewinderGenerator {
+    fn default() -> Self {
+        Self::new(Bias::default())
+    }
+}
+
+impl MazeGenerator for SidewinderGenerator {
+    fn name(&self) -> &'static str {
+        "sidewinder"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let index = match self.next_index {
+            Some(index) => index,
+            None => {
+                fill_all_walls(grid);
+                self.next_index = Some(0);
+                self.in_run = Some(Grid::new(grid.width(), grid.height(), &false));
+                return StepResult::Pause(250);
+            },
+        };
+
+        if index == (grid.width() - 1) * (grid.height() - 1) {
+            self.clear_run();
+            open_random_entrance_and_exit(rng, grid);
+            return StepResult::Done;
+        }
+
+        let point = self.bias.scan_cell(grid, index);
+        if point.0 == self.bias.scan_cell(grid, 0).0 {
+            // Starting a new row.
+            self.clear_run();
+        }
+
+        if let Some(ref mut in_run) = self.in_run {
+            in_run[&point] = true;
+        }
+
+        self.run.push(point.clone());
+
+        let vertical = self.bias.vertical_neighbor(grid, &point);
+        let horizontal = self.bias.horizontal_neighbor(grid, &point);
+        match (vertical, horizontal) {
+            // The last row can only be a corridor.
+            (None, Some(next)) => set_wall_between(grid, &point, &next, EdgeState::Off),
+            (Some(_), Some(next)) if !rng.gen_bool(CLOSE_RUN_CHANCE) => {
+                set_wall_between(grid, &point, &next, EdgeState::Off)
+            },
+            (Some(_), _) => {
+                let from = &self.run[rng.gen_range(0, self.run.len())];
+                let to = self.bias.vertical_neighbor(grid, from).unwrap();
+                set_wall_between(grid, from, &to, EdgeState::Off);
+                self.clear_run();
+            },
+            (None, None) => (),
+        }
+
+        self.next_index = Some(index + 1);
+        StepResult::Continue
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if self.run.last() == Some(point) {
+            Some(Highlight::Current)
+        }
+        else if self.in_run.as_ref().is_some_and(|in_run| in_run[point]) {
+            Some(Highlight::Frontier)
+        }
+        else {
+            None
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        Some(format!("bias {}", self.bias))
+    }
+}
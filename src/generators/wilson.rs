@@ -0,0 +1,185 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState};
+use super::{
+    fill_all_walls,
+    maze_neighbors,
+    open_random_entrance_and_exit,
+    random_maze_cell,
+    set_wall_between,
+    Highlight,
+    MazeGenerator,
+    StepResult,
+};
+
+/// Builds a maze chosen uniformly from all possible perfect mazes, using Wilson's algorithm. From
+/// a random cell outside the maze, it random-walks until it reaches the maze, erasing any loops in
+/// the walk as it goes, then carves the walk into the maze.
+pub struct WilsonGenerator {
+    walker: Option<LoopErasedWalker>,
+}
+
+/// Adds cells to a partially built maze with loop-erased random walks until every cell is in it.
+pub(crate) struct LoopErasedWalker {
+    in_maze: Grid<bool>,
+
+    // Cells that might still be outside the maze, in the random order they'll be walked from.
+    remaining: Vec<XY>,
+
+    // The loop-erased walk so far, and where each cell appears in it.
+    walk: Vec<XY>,
+    walk_index: Grid<Option<usize>>,
+
+    walk_steps: usize,
+}
+
+impl WilsonGenerator {
+    pub fn new() -> WilsonGenerator {
+        WilsonGenerator {
+            walker: None,
+        }
+    }
+}
+
+impl Default for WilsonGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoopErasedWalker {
+    /// Starts walking from the cells that aren't already marked in `in_maze`, which must have at
+    /// least one cell in it.
+    pub(crate) fn new(rng: &mut StdRng, grid: &CellGrid, in_maze: Grid<bool>) -> LoopErasedWalker {
+        let mut remaining = Vec::new();
+        for y in 0 .. grid.height() - 1 {
+            for x in 0 .. grid.width() - 1 {
+                if !in_maze[XY(x, y)] {
+                    remaining.push(XY(x, y));
+                }
+            }
+        }
+
+        remaining.shuffle(rng);
+
+        LoopErasedWalker {
+            in_maze,
+            remaining,
+            walk: Vec::new(),
+            walk_index: Grid::new(grid.width(), grid.height(), &None),
+            walk_steps: 0,
+        }
+    }
+
+    /// How many random steps have been taken in total.
+    pub(crate) fn walk_steps(&self) -> usize {
+        self.walk_steps
+    }
+
+    fn push_walk(&mut self, point: XY) {
+        self.walk_index[&point] = Some(self.walk.len());
+        self.walk.push(point);
+    }
+
+    /// Adds the whole walk to the maze, ending with the wall into the maze cell it reached.
+    fn carve_walk(&mut self, grid: &mut CellGrid, reached: &XY) {
+        for (i, point) in self.walk.iter().enumerate() {
+            let next = self.walk.get(i + 1).unwrap_or(reached);
+            set_wall_between(grid, point, next, EdgeState::Off);
+            self.in_maze[point] = true;
+            self.walk_index[point] = None;
+        }
+
+        self.walk.clear();
+    }
+
+    /// Takes one step of the current walk, or starts a new one. Returns false once every cell is
+    /// in the maze.
+    pub(crate) fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> bool {
+        // Start a new walk from a cell that isn't in the maze yet.
+        if self.walk.is_empty() {
+            loop {
+                match self.remaining.pop() {
+                    Some(point) if !self.in_maze[&point] => {
+                        self.push_walk(point);
+                        return true;
+                    },
+                    Some(_) => (),
+                    None => return false,
+                }
+            }
+        }
+
+        let current = self.walk.last().unwrap().clone();
+        let neighbors = maze_neighbors(grid, &current);
+        let next = neighbors[rng.gen_range(0, neighbors.len())].clone();
+        self.walk_steps += 1;
+
+        if self.in_maze[&next] {
+            self.carve_walk(grid, &next);
+        }
+        else if let Some(loop_start) = self.walk_index[&next] {
+            // The walk crossed itself, so erase the loop it just made.
+            for point in self.walk.drain(loop_start + 1 ..) {
+                self.walk_index[point] = None;
+            }
+        }
+        else {
+            self.push_walk(next);
+        }
+
+        true
+    }
+
+    pub(crate) fn highlight(&self, point: &XY) -> Option<Highlight> {
+        if self.walk.last() == Some(point) {
+            Some(Highlight::Current)
+        }
+        else if self.walk_index[point].is_some() {
+            Some(Highlight::Walk)
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl MazeGenerator for WilsonGenerator {
+    fn name(&self) -> &'static str {
+        "wilson"
+    }
+
+    fn step(&mut self, rng: &mut StdRng, grid: &mut CellGrid) -> StepResult {
+        let walker = match self.walker {
+            Some(ref mut walker) => walker,
+            None => {
+                fill_all_walls(grid);
+
+                // The maze starts out as a single cell.
+                let mut in_maze = Grid::new(grid.width(), grid.height(), &false);
+                in_maze[random_maze_cell(rng, grid)] = true;
+                self.walker = Some(LoopErasedWalker::new(rng, grid, in_maze));
+                return StepResult::Pause(250);
+            },
+        };
+
+        if walker.step(rng, grid) {
+            StepResult::Continue
+        }
+        else {
+            open_random_entrance_and_exit(rng, grid);
+            StepResult::Done
+        }
+    }
+
+    fn highlight(&self, point: &XY) -> Option<Highlight> {
+        self.walker.as_ref()?.highlight(point)
+    }
+
+    fn summary(&self) -> Option<String> {
+        Some(format!("{} walk steps", self.walker.as_ref()?.walk_steps()))
+    }
+}
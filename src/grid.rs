@@ -7,6 +7,7 @@ use std::{
 /// It dereferences to a slice of [`CellType`], so you can directly manipulate
 /// it via regular (mutable) slice methods. In addition, you can index
 /// into it by `(row, column)` pairs.
+#[derive(Clone)]
 pub struct Grid<CellType>
     where CellType : Clone {
     width: usize,
@@ -45,7 +46,7 @@ impl<CellType> Grid<CellType>
         Grid {
             width,
             height,
-            cells: vec![template.clone(); (width * height) as usize],
+            cells: vec![template.clone(); width * height],
         }
     }
 }
@@ -54,14 +55,14 @@ impl<CellType> Index<RC> for Grid<CellType>
     where CellType : Clone {
     type Output = CellType;
     fn index(&self, RC(row, col): RC) -> &Self::Output {
-        &self.cells[(row * self.width + col) as usize]
+        &self.cells[row * self.width + col]
     }
 }
 
 impl<CellType> IndexMut<RC> for Grid<CellType>
     where CellType : Clone {
     fn index_mut(&mut self, RC(row, col): RC) -> &mut Self::Output {
-        &mut self.cells[(row * self.width + col) as usize]
+        &mut self.cells[row * self.width + col]
     }
 }
 
@@ -69,7 +70,7 @@ impl<CellType> Index<&XY> for Grid<CellType>
     where CellType : Clone {
     type Output = CellType;
     fn index(&self, XY(x, y): &XY) -> &Self::Output {
-        &self.cells[(*y * self.width + *x) as usize]
+        &self.cells[*y * self.width + *x]
     }
 }
 
@@ -84,7 +85,7 @@ impl<CellType> Index<XY> for Grid<CellType>
 impl<CellType> IndexMut<&XY> for Grid<CellType>
     where CellType : Clone {
     fn index_mut(&mut self, XY(x, y): &XY) -> &mut Self::Output {
-        &mut self.cells[(*y * self.width + *x) as usize]
+        &mut self.cells[*y * self.width + *x]
     }
 }
 
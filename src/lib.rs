@@ -0,0 +1,18 @@
+//! Maze generation.
+//!
+//! [`maze::GridState`] drives one of the [`generators`] to build a maze step by step into a
+//! [`maze::CellGrid`], so callers can either animate generation or run it to completion in one go.
+//! Finished mazes can be changed further with [`postprocess`], solved with [`solve`] or watched being solved by
+//! [`agents`], rasterized with [`render`] and saved with [`export`].
+
+extern crate png;
+extern crate rand;
+
+pub mod agents;
+pub mod export;
+pub mod generators;
+pub mod grid;
+pub mod maze;
+pub mod postprocess;
+pub mod render;
+pub mod solve;
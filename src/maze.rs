@@ -0,0 +1,773 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::generators::disjoint_set::DisjointSet;
+use crate::generators::{self, Bias, GrowingTreePolicy, MazeGenerator, StepResult};
+use crate::grid::{Grid, XY};
+use crate::postprocess;
+use crate::solve;
+
+/// The parameters that control how a maze is generated.
+#[derive(Clone, Debug)]
+pub struct GenParams {
+    /// The width of the grid, including the extra column that holds the right border.
+    pub width: usize,
+
+    /// The height of the grid, including the extra row that holds the top border.
+    pub height: usize,
+
+    /// How many turning points the generated path has, including the start and end.
+    pub path_point_count: usize,
+
+    /// The chance that any unset edge is turned on when filling in walls randomly.
+    pub edge_enabled_chance: f64,
+
+    /// Controls how many random enclosures are broken up before sweeping the grid in order. The
+    /// number of random tries is the cell count divided by this factor.
+    pub random_invalid_edge_erase_factor: usize,
+
+    /// The name of the generator to use. See [`generators::GENERATOR_NAMES`].
+    pub generator: String,
+
+    /// For the hybrid Aldous-Broder/Wilson generator, the fraction of cells to visit with the
+    /// plain random walk before switching to loop-erased walks.
+    pub hybrid_switch_fraction: f64,
+
+    /// For the growing-tree generator, how the next active cell is picked.
+    pub growing_tree_policy: GrowingTreePolicy,
+
+    /// For the binary tree and sidewinder generators, the corner the maze leans towards.
+    pub bias: Bias,
+
+    /// For the recursive division generator, chambers this many cells across or smaller in both
+    /// directions are left open as rooms.
+    pub room_size: usize,
+
+    /// The fraction of dead ends (0 to 1) to remove once the maze is finished, adding loops.
+    pub braid_fraction: f64,
+
+    /// The fraction of maze cells (0 to 1) to cull into solid rock once the maze is finished,
+    /// by repeatedly filling in dead ends.
+    pub sparseness: f64,
+
+    /// For the path-fill generator, keep fixing the maze until [`GridState::check_maze`] finds
+    /// nothing wrong with it.
+    pub strict: bool,
+
+    /// Once the maze is finished, move the entrance and exit to the two border cells that are
+    /// furthest apart.
+    pub longest_path: bool,
+}
+
+/// Why a set of [`GenParams`] can't be used to generate a maze.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamError {
+    GridTooSmall { width: usize, height: usize },
+    TooFewPathPoints(usize),
+    TooManyPathPoints { requested: usize, max: usize },
+    EdgeChanceOutOfRange(f64),
+    EraseFactorZero,
+    UnknownGenerator(String),
+    HybridFractionOutOfRange(f64),
+    RoomSizeZero,
+    BraidFractionOutOfRange(f64),
+    SparsenessOutOfRange(f64),
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamError::GridTooSmall { width, height } =>
+                write!(f, "grid size {}x{} is too small; width and height must be at least {}", width, height, GenParams::MIN_GRID_SIZE),
+            ParamError::TooFewPathPoints(count) =>
+                write!(f, "path point count {} is too small; the path needs at least a start and an end", count),
+            ParamError::TooManyPathPoints { requested, max } =>
+                write!(f, "path point count {} does not fit in the grid; at most {} points fit", requested, max),
+            ParamError::EdgeChanceOutOfRange(chance) =>
+                write!(f, "edge enabled chance {} must be greater than 0 and at most 1", chance),
+            ParamError::EraseFactorZero =>
+                write!(f, "random invalid edge erase factor must be at least 1"),
+            ParamError::UnknownGenerator(name) =>
+                write!(f, "unknown generator '{}'; expected one of: {}", name, generators::GENERATOR_NAMES.join(", ")),
+            ParamError::HybridFractionOutOfRange(fraction) =>
+                write!(f, "hybrid switch fraction {} must be between 0 and 1", fraction),
+            ParamError::RoomSizeZero =>
+                write!(f, "room size must be at least 1"),
+            ParamError::BraidFractionOutOfRange(fraction) =>
+                write!(f, "braid fraction {} must be between 0 and 1", fraction),
+            ParamError::SparsenessOutOfRange(fraction) =>
+                write!(f, "sparseness {} must be between 0 and 1", fraction),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+impl Default for GenParams {
+    fn default() -> Self {
+        GenParams {
+            width: 20,
+            height: 20,
+            path_point_count: 12,
+            edge_enabled_chance: 0.7,
+            random_invalid_edge_erase_factor: 4,
+            generator: String::from("path-fill"),
+            hybrid_switch_fraction: 0.5,
+            growing_tree_policy: GrowingTreePolicy::default(),
+            bias: Bias::default(),
+            room_size: 1,
+            braid_fraction: 0.0,
+            sparseness: 0.0,
+            strict: false,
+            longest_path: false,
+        }
+    }
+}
+
+impl GenParams {
+    /// The smallest grid that still has room for a path, counting the extra border row and column.
+    pub const MIN_GRID_SIZE: usize = 3;
+
+    /// The most path points that can fit in the grid. The path alternates between horizontal and
+    /// vertical moves that each travel at least one cell and never double back, so each direction
+    /// can only be taken as many times as there are cells to move through.
+    pub fn max_path_point_count(&self) -> usize {
+        let moves_per_direction = self.width.min(self.height).saturating_sub(2);
+        (moves_per_direction * 2) + 1
+    }
+
+    /// Checks that a maze can actually be generated with these parameters.
+    pub fn validate(&self) -> Result<(), ParamError> {
+        if self.width < Self::MIN_GRID_SIZE || self.height < Self::MIN_GRID_SIZE {
+            return Err(ParamError::GridTooSmall { width: self.width, height: self.height });
+        }
+
+        // Only the path-fill generator lays down a path with a set number of points.
+        if self.generator == "path-fill" {
+            if self.path_point_count < 2 {
+                return Err(ParamError::TooFewPathPoints(self.path_point_count));
+            }
+
+            let max = self.max_path_point_count();
+            if self.path_point_count > max {
+                return Err(ParamError::TooManyPathPoints { requested: self.path_point_count, max });
+            }
+        }
+
+        if !(self.edge_enabled_chance > 0.0 && self.edge_enabled_chance <= 1.0) {
+            return Err(ParamError::EdgeChanceOutOfRange(self.edge_enabled_chance));
+        }
+
+        if self.random_invalid_edge_erase_factor == 0 {
+            return Err(ParamError::EraseFactorZero);
+        }
+
+        if !generators::GENERATOR_NAMES.contains(&self.generator.as_str()) {
+            return Err(ParamError::UnknownGenerator(self.generator.clone()));
+        }
+
+        if !(0.0 ..= 1.0).contains(&self.hybrid_switch_fraction) {
+            return Err(ParamError::HybridFractionOutOfRange(self.hybrid_switch_fraction));
+        }
+
+        if self.room_size == 0 {
+            return Err(ParamError::RoomSizeZero);
+        }
+
+        if !(0.0 ..= 1.0).contains(&self.braid_fraction) {
+            return Err(ParamError::BraidFractionOutOfRange(self.braid_fraction));
+        }
+
+        if !(0.0 ..= 1.0).contains(&self.sparseness) {
+            return Err(ParamError::SparsenessOutOfRange(self.sparseness));
+        }
+
+        Ok(())
+    }
+}
+
+/// The problems found in a maze by [`GridState::check_maze`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MazeReport {
+    /// Cells that can't be reached from the start.
+    pub unreachable_cells: Vec<XY>,
+
+    /// Cells with walls on every side.
+    pub closed_cells: Vec<XY>,
+
+    /// One open wall from each loop, given as the cells on either side of it. Putting the wall back
+    /// breaks the loop without cutting anything off.
+    pub loops: Vec<(XY, XY)>,
+
+    /// Where the path starts and ends, if the maze has one.
+    pub start: Option<XY>,
+    pub end: Option<XY>,
+
+    pub start_on_border: bool,
+    pub end_on_border: bool,
+}
+
+impl MazeReport {
+    /// Whether the maze is perfect, with nothing wrong at all.
+    pub fn is_clean(&self) -> bool {
+        self.unreachable_cells.is_empty() &&
+        self.closed_cells.is_empty() &&
+        self.loops.is_empty() &&
+        self.start_on_border &&
+        self.end_on_border
+    }
+}
+
+impl fmt::Display for MazeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} unreachable cells, {} closed cells, {} loops, start {}, end {}",
+            self.unreachable_cells.len(),
+            self.closed_cells.len(),
+            self.loops.len(),
+            if self.start_on_border { "on border" } else { "not on border" },
+            if self.end_on_border { "on border" } else { "not on border" })
+    }
+}
+
+/// What role a cell plays in the generated maze.
+#[derive(Clone, Debug)]
+pub enum GridCellKind {
+    Empty,
+    Path(usize),
+    PathIntermediate,
+    End,
+
+    /// Solid rock that was culled from the maze. It has walls on every side and isn't part of
+    /// the maze any more.
+    Removed,
+}
+
+/// The state of a single wall during and after generation.
+#[derive(Clone, Debug)]
+pub enum EdgeState {
+    Unset,
+    Off,
+    ProvisionallyOn,
+    On,
+}
+
+/// A cell in the maze grid. Each cell owns the wall on its left and the wall below it.
+#[derive(Clone, Debug)]
+pub struct GridCell {
+    pub kind: GridCellKind,
+    pub left_edge: EdgeState,
+    pub bottom_edge: EdgeState,
+}
+
+/// A grid of maze cells. The last row and column sit just outside the maze and only exist to
+/// hold the top and right border walls.
+pub type CellGrid = Grid<GridCell>;
+
+/// Drives a [`MazeGenerator`] to build a maze one step at a time. Call [`GridState::update`]
+/// repeatedly, or [`GridState::generate_to_completion`] to run every step at once.
+pub struct GridState {
+    rng: rand::rngs::StdRng,
+    grid: CellGrid,
+    path: Vec<XY>,
+
+    // The shortest route from entrance to exit, found once the maze is done.
+    solution: Option<Vec<XY>>,
+
+    // What the post-processing passes did once the maze was done.
+    postprocess_report: postprocess::PostprocessReport,
+
+    generator: Box<dyn MazeGenerator>,
+    params: GenParams,
+    is_done: bool,
+
+    // Set when the generator asked for a pause. No more steps happen until this time.
+    paused_until: Option<Instant>,
+
+    // When false, pauses between steps are skipped, so the maze is generated as fast as possible.
+    // Used for headless generation.
+    use_delays: bool,
+}
+
+impl GridCell {
+    pub fn new() -> GridCell {
+        GridCell {
+            kind: GridCellKind::Empty,
+            left_edge: EdgeState::Unset,
+            bottom_edge: EdgeState::Unset,
+        }
+    }
+
+    pub fn has_left_edge(&self) -> bool {
+        matches!(self.left_edge, EdgeState::On | EdgeState::ProvisionallyOn)
+    }
+
+    pub fn has_bottom_edge(&self) -> bool {
+        matches!(self.bottom_edge, EdgeState::On | EdgeState::ProvisionallyOn)
+    }
+}
+
+impl Default for GridCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GridState {
+    /// Creates a maze using the generator named in the given parameters, which are validated
+    /// first. When `use_delays` is false, the pauses between steps are skipped.
+    pub fn new(seed: u64, params: GenParams, use_delays: bool) -> Result<GridState, ParamError> {
+        params.validate()?;
+
+        let mut gs = GridState {
+            rng: rand::rngs::StdRng::seed_from_u64(seed),
+            grid: CellGrid::new(params.width, params.height, &GridCell::new()),
+            path: Vec::new(),
+            solution: None,
+            postprocess_report: postprocess::PostprocessReport::default(),
+            generator: generators::create(&params.generator, &params).unwrap(),
+            params,
+            is_done: false,
+            paused_until: None,
+            use_delays,
+        };
+
+        gs.start_generate_maze();
+
+        Ok(gs)
+    }
+
+    /// Throws away the current maze and starts generating a new one from the same RNG.
+    pub fn start_generate_maze(&mut self) {
+        self.grid = CellGrid::new(self.grid.width(), self.grid.height(), &GridCell::new());
+        self.generator = generators::create(&self.params.generator, &self.params).unwrap();
+        self.is_done = false;
+        self.paused_until = None;
+        self.path.clear();
+        self.solution = None;
+        self.postprocess_report = postprocess::PostprocessReport::default();
+    }
+
+    /// Switches to a different generator by name and starts a new maze with it.
+    pub fn set_generator(&mut self, name: &str) -> Result<(), ParamError> {
+        let params = GenParams { generator: String::from(name), ..self.params.clone() };
+        params.validate()?;
+
+        self.params = params;
+        self.start_generate_maze();
+        Ok(())
+    }
+
+    /// Advances generation by one step, unless the generator asked for a pause that hasn't
+    /// finished yet. Returns true from the step that finishes the maze.
+    pub fn update(&mut self) -> bool {
+        if self.is_done {
+            return false;
+        }
+
+        if let Some(paused_until) = self.paused_until {
+            if Instant::now() < paused_until {
+                return false;
+            }
+
+            self.paused_until = None;
+        }
+
+        match self.generator.step(&mut self.rng, &mut self.grid) {
+            StepResult::Continue => (),
+            StepResult::Pause(millis) => {
+                self.path = Self::extract_path(&self.grid);
+                if self.use_delays {
+                    self.paused_until = Some(Instant::now() + Duration::from_millis(millis));
+                }
+            },
+            StepResult::Done => {
+                self.is_done = true;
+
+                if self.params.sparseness > 0.0 {
+                    let culled = postprocess::cull(&mut self.rng, &mut self.grid, self.params.sparseness);
+                    self.postprocess_report.cells_culled = Some(culled);
+                }
+
+                if self.params.braid_fraction > 0.0 {
+                    let walls_removed = postprocess::braid(&mut self.rng, &mut self.grid, self.params.braid_fraction);
+                    self.postprocess_report.walls_braided = Some(walls_removed);
+                }
+
+                if self.params.longest_path {
+                    let distance = postprocess::place_entrance_and_exit_far_apart(&mut self.rng, &mut self.grid);
+                    self.postprocess_report.entrance_exit_distance = Some(distance);
+                }
+
+                self.path = Self::extract_path(&self.grid);
+                self.solution = solve::solve(&self.grid);
+            },
+        };
+
+        self.is_done
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    /// The generator building this maze.
+    pub fn generator(&self) -> &dyn MazeGenerator {
+        self.generator.as_ref()
+    }
+
+    /// The maze grid as generated so far.
+    pub fn grid(&self) -> &CellGrid {
+        &self.grid
+    }
+
+    /// The turning points of the generated path, from start to end.
+    pub fn path(&self) -> &[XY] {
+        &self.path
+    }
+
+    /// The shortest route from the entrance to the exit of the finished maze, which might not be
+    /// the same as the path the generator marked. `None` until the maze is done.
+    pub fn solution(&self) -> Option<&[XY]> {
+        self.solution.as_deref()
+    }
+
+    /// What the post-processing passes did to the finished maze.
+    pub fn postprocess_report(&self) -> &postprocess::PostprocessReport {
+        &self.postprocess_report
+    }
+
+    /// Runs every step of generation back to back until the maze is done, without waiting on any
+    /// pauses.
+    pub fn generate_to_completion(&mut self) {
+        let use_delays = self.use_delays;
+        self.use_delays = false;
+
+        while !self.is_done() {
+            self.update();
+        }
+
+        self.use_delays = use_delays;
+    }
+
+    /// Turns on the walls around the outside of the maze and clears everything else.
+    pub fn fill_borders(grid: &mut CellGrid) {
+        let width = grid.width();
+        let height = grid.height();
+
+        // Turn on walls at the borders.
+        for (y, row) in grid.chunks_mut(width).enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                cell.bottom_edge = if (x != width - 1) && (y == 0 || y == height - 1) { EdgeState::On } else { EdgeState::Unset };
+                cell.left_edge = if (y != height - 1) && (x == 0 || x == width - 1) { EdgeState::On } else { EdgeState::Unset };
+            }
+        }
+    }
+
+    pub(crate) fn erase_start_or_end_edge(rng: &mut rand::rngs::StdRng, grid: &mut CellGrid, XY(x, y): &XY) {
+        let x = *x;
+        let y = *y;
+
+        let has_vertical_edge = x == 0 || x == grid.width() - 2;
+        let has_horizontal_edge = y == 0 || y == grid.height() - 2;
+
+        // Decide whether to erase a vertical edge or horizontal edge. The chosen edge can only be
+        // erased if the cell has such an edge available to erase, so keep looping until the intent
+        // and available edge lines up.
+        let mut erase_vertical_edge = rng.gen_bool(0.5);
+        while erase_vertical_edge != has_vertical_edge && erase_vertical_edge == has_horizontal_edge {
+            erase_vertical_edge = rng.gen_bool(0.5);
+        }
+
+        // Erasing a vertical edge on the right border means going to the next cell over (just
+        // outside the border) and erasing the left edge.
+        if erase_vertical_edge {
+            if x == grid.width() - 2 {
+                grid[XY(x + 1, y)].left_edge = EdgeState::Off;
+            } else {
+                grid[XY(x, y)].left_edge = EdgeState::Off;
+            }
+        }
+
+        // Erasing a horizontal edge on the top edge means going one cell up (just outside the
+        // border) and erasing the bottom edge.
+        else {
+            if y == grid.height() - 2 {
+                grid[XY(x, y + 1)].bottom_edge = EdgeState::Off;
+            } else {
+                grid[XY(x, y)].bottom_edge = EdgeState::Off;
+            }
+        }
+    }
+
+    pub(crate) fn is_valid_start_or_end(
+        grid: &CellGrid,
+        XY(x, y): &XY,
+        ) -> bool
+    {
+        // Start and end points need to be somewhere on the border, within the confines of the maze.
+        *x == 0 || *x == grid.width() - 2 ||
+        *y == 0 || *y == grid.height() - 2
+    }
+
+    pub(crate) fn count_exits(grid: &CellGrid, point: &XY) -> usize {
+        let mut exit_count = 0;
+
+        let cell = &grid[point];
+        if !cell.has_left_edge() {
+            exit_count += 1;
+        }
+
+        if !cell.has_bottom_edge() {
+            exit_count += 1;
+        }
+
+        if !grid[XY(point.0 + 1, point.1)].has_left_edge() {
+            exit_count += 1;
+        }
+
+        if !grid[XY(point.0, point.1 + 1)].has_bottom_edge() {
+            exit_count += 1;
+        }
+
+        exit_count
+    }
+
+    pub(crate) fn has_inner_grid_walls(grid: &CellGrid) -> bool {
+        // For every 2x2 sub-grid, there must be at least one inner wall
+        for y in 0 .. grid.height() - 1 {
+            for x in 0 .. grid.width() - 1 {
+                if grid[XY(x+1, y)].has_left_edge() {
+                    continue;
+                }
+
+                if grid[XY(x, y+1)].has_bottom_edge() {
+                    continue;
+                }
+
+                let cell = &grid[XY(x+1, y+1)];
+                if cell.has_left_edge() || cell.has_bottom_edge() {
+                    continue;
+                }
+
+                //println!("no inner edges");
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Whether the maze is a perfect maze with its start and end on the border. See
+    /// [`GridState::check_maze`].
+    pub(crate) fn has_valid_edges(grid: &CellGrid) -> bool {
+        Self::check_maze(grid).is_clean()
+    }
+
+    /// Checks everything that should be true of a perfect maze: every cell can be reached from the
+    /// start, no cell is walled in on all sides, there is exactly one route between any two cells,
+    /// and the start and end are on the border. Culled cells are ignored.
+    pub fn check_maze(grid: &CellGrid) -> MazeReport {
+        let is_on_path = |point: &XY| {
+            matches!(grid[point].kind, GridCellKind::Path(_) | GridCellKind::PathIntermediate | GridCellKind::End)
+        };
+        let is_removed = |point: &XY| matches!(grid[point].kind, GridCellKind::Removed);
+
+        let mut report = MazeReport::default();
+        let (start, end) = solve::find_start_and_end(grid);
+        report.start = start;
+        report.end = end;
+
+        report.start_on_border = report.start.as_ref().is_some_and(|start| Self::is_valid_start_or_end(grid, start));
+        report.end_on_border = report.end.as_ref().is_some_and(|end| Self::is_valid_start_or_end(grid, end));
+
+        let traversal = Self::visit_all(grid, report.start.as_ref().unwrap_or(&XY(0, 0)));
+        for y in 0 .. grid.height() - 1 {
+            for x in 0 .. grid.width() - 1 {
+                let point = XY(x, y);
+                if is_removed(&point) {
+                    continue;
+                }
+
+                if Self::count_exits(grid, &point) == 0 {
+                    report.closed_cells.push(point.clone());
+                }
+
+                if !traversal[&point] {
+                    report.unreachable_cells.push(point);
+                }
+            }
+        }
+
+        // Every open wall that joins two cells that were already connected closes a loop. Walls
+        // between path cells go first so that the path itself is never reported as a loop.
+        let mut open_walls = Vec::new();
+        for y in 0 .. grid.height() - 1 {
+            for x in 0 .. grid.width() - 1 {
+                let point = XY(x, y);
+                if x > 0 && !grid[&point].has_left_edge() {
+                    open_walls.push((XY(x - 1, y), point.clone()));
+                }
+
+                if y > 0 && !grid[&point].has_bottom_edge() {
+                    open_walls.push((XY(x, y - 1), point));
+                }
+            }
+        }
+
+        open_walls.retain(|(a, b)| !is_removed(a) && !is_removed(b));
+        open_walls.sort_by_key(|(a, b)| !(is_on_path(a) && is_on_path(b)));
+
+        let maze_width = grid.width() - 1;
+        let mut connected = DisjointSet::new(maze_width * (grid.height() - 1));
+        for (a, b) in open_walls {
+            if !connected.union((a.1 * maze_width) + a.0, (b.1 * maze_width) + b.0) {
+                report.loops.push((a, b));
+            }
+        }
+
+        report
+    }
+
+    /// Which maze cells can be reached from `start`. The result only covers the maze cells, not
+    /// the extra border row and column.
+    pub(crate) fn visit_all(grid: &CellGrid, start: &XY) -> Grid<bool> {
+        let distances = Self::distances_from(grid, start);
+        let mut traversal = Grid::new(distances.width(), distances.height(), &false);
+        for (visited, distance) in traversal.iter_mut().zip(distances.iter()) {
+            *visited = distance.is_some();
+        }
+
+        traversal
+    }
+
+    /// How many steps it takes to get from `start` to every maze cell, or `None` for cells that
+    /// can't be reached. Like [`GridState::visit_all`], the result only covers the maze cells.
+    pub fn distances_from(grid: &CellGrid, start: &XY) -> Grid<Option<usize>> {
+        let mut distances = Grid::new(grid.width() - 1, grid.height() - 1, &None);
+        let mut queue = VecDeque::new();
+        distances[start] = Some(0);
+        queue.push_back(start.clone());
+
+        // A breadth-first search, so that the first time a cell is reached is along the shortest
+        // route to it.
+        while let Some(point) = queue.pop_front() {
+            // Culled cells are walled off, but don't count as being reachable even if a wall
+            // around them is missing.
+            if let GridCellKind::Removed = grid[&point].kind {
+                continue;
+            }
+
+            let XY(x, y) = point;
+            let distance = distances[&point].unwrap() + 1;
+            let mut neighbors = Vec::with_capacity(4);
+
+            if x > 0 && !grid[XY(x, y)].has_left_edge() {
+                neighbors.push(XY(x - 1, y));
+            }
+
+            if y > 0 && !grid[XY(x, y)].has_bottom_edge() {
+                neighbors.push(XY(x, y - 1));
+            }
+
+            if x < distances.width() - 1 && !grid[XY(x + 1, y)].has_left_edge() {
+                neighbors.push(XY(x + 1, y));
+            }
+
+            if y < distances.height() - 1 && !grid[XY(x, y + 1)].has_bottom_edge() {
+                neighbors.push(XY(x, y + 1));
+            }
+
+            for neighbor in neighbors {
+                if distances[&neighbor].is_none() && !matches!(grid[&neighbor].kind, GridCellKind::Removed) {
+                    distances[&neighbor] = Some(distance);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        distances
+    }
+
+    pub(crate) fn find_enclosed_section(grid: &CellGrid, point: &XY) -> Option<Vec<XY>> {
+        // Culled cells are supposed to be enclosed.
+        if let GridCellKind::Removed = grid[point].kind {
+            return None;
+        }
+
+        let traversal = Self::visit_all(grid, point);
+
+        // Only look at cells that were touched by the traversal
+        let mut section = Vec::<XY>::new();
+        for (i, _) in traversal.iter().enumerate().filter(|(_, &visited)| visited) {
+            let p = traversal.index_to_xy(i);
+            match grid[&p].kind {
+                // Throw away the enclosed section if it ever touched the path, because that
+                // means it had a way out of the maze.
+                GridCellKind::Path(_) | GridCellKind::PathIntermediate | GridCellKind::End => return None,
+
+                // Otherwise, it was non-path cell that might have been self-enclosed, so add it
+                // to the path.
+                _ => section.push(p),
+            }
+        }
+
+        Some(section)
+    }
+
+    /// The turning points of the generated path, in order from start to end.
+    pub fn extract_path(grid: &CellGrid) -> Vec<XY> {
+        let mut points: Vec<(usize, XY)> = grid.iter().enumerate().filter_map(|(i, cell)| {
+            match cell.kind {
+                GridCellKind::Path(n) => Some((n, grid.index_to_xy(i))),
+                GridCellKind::End => Some((usize::MAX, grid.index_to_xy(i))),
+                _ => None
+            }
+        }).collect();
+
+        points.sort_by_key(|(n, _)| *n);
+        points.into_iter().map(|(_, point)| point).collect()
+    }
+
+    /// Renders the maze as text, with the top row of the maze first. Path cells are marked when
+    /// `show_path` is set.
+    pub fn to_text(&self, show_path: bool) -> String {
+        let grid = &self.grid;
+        let mut text = String::new();
+
+        for line_y in (0 .. grid.height()).rev() {
+            // Horizontal walls along this grid line.
+            for x in 0 .. grid.width() - 1 {
+                text.push('+');
+                text.push_str(if grid[XY(x, line_y)].has_bottom_edge() { "---" } else { "   " });
+            }
+            text.push_str("+\n");
+
+            // The row of cells just below this grid line, with their vertical walls.
+            if line_y > 0 {
+                let y = line_y - 1;
+                for x in 0 .. grid.width() {
+                    text.push(if grid[XY(x, y)].has_left_edge() { '|' } else { ' ' });
+
+                    if x < grid.width() - 1 {
+                        text.push_str(
+                            match grid[XY(x, y)].kind {
+                                GridCellKind::Path(_) | GridCellKind::End if show_path => " * ",
+                                GridCellKind::PathIntermediate if show_path => " . ",
+                                GridCellKind::Removed => "###",
+                                _ => "   ",
+                            });
+                    }
+                }
+                text.push('\n');
+            }
+        }
+
+        text
+    }
+}
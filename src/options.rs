@@ -0,0 +1,139 @@
+use std::str::FromStr;
+
+use mazes::{
+    generators::GENERATOR_NAMES,
+    maze::GenParams,
+    render::RenderOptions,
+    solve::Heuristic,
+};
+
+const USAGE: &str = "\
+Usage: mazes [options]
+
+Generation:
+  -r, --seed <n>          Seed for the random number generator
+  -g, --generator <name>  Generation algorithm, one of the generators listed below
+      --width <n>         Grid width, including the extra border column
+      --height <n>        Grid height, including the extra border row
+      --path-points <n>   Number of turning points on the path, including start and end
+      --edge-chance <f>   Chance (0 to 1] that each wall is turned on when filling randomly
+      --erase-factor <n>  Cell count divided by this is the number of random enclosure fixes
+      --hybrid-fraction <f>
+                          Fraction of cells aldous-broder-wilson visits before switching
+      --tree-policy <p>   How growing-tree picks the next cell: newest, oldest, random, or a
+                          weighted mix like newest:75,random:25
+      --bias <dir>        Corner binary-tree and sidewinder lean towards: ne, nw, se, or sw
+      --room-size <n>     recursive-division leaves chambers this size or smaller as open rooms
+      --braid <f>         Fraction of dead ends (0 to 1) to remove afterwards, adding loops
+      --sparseness <f>    Fraction of cells (0 to 1) to fill in afterwards by culling dead ends
+      --strict            Keep fixing path-fill mazes until they are perfect
+      --longest-path      Put the entrance and exit on the border cells furthest apart
+
+Solving:
+      --heuristic <h>     Estimate the A* solver uses: manhattan, euclidean, or zero
+      --agents            In headless mode, also run every step-wise solving agent
+
+Output:
+      --headless          Generate without a window and print the maze as text
+      --output <file>     Generate without a window and save to a .png or .svg file
+      --cell-size <n>     Size of one cell in pixels
+      --wall-thickness <n>
+                          Thickness of each wall in pixels
+      --margin <n>        Blank space around the maze in pixels
+      --no-path           Don't show the generated path
+  -h, --help              Show this help
+";
+
+/// The usage text followed by the list of generators that can be picked.
+pub fn help_text() -> String {
+    format!("{}\nGenerators: {}\n", USAGE, GENERATOR_NAMES.join(", "))
+}
+
+/// Everything that can be set from the command line.
+pub struct Options {
+    pub seed: Option<u64>,
+    pub headless: bool,
+    pub output: Option<String>,
+    pub params: GenParams,
+    pub render_options: RenderOptions,
+    pub heuristic: Heuristic,
+    pub run_agents: bool,
+    pub show_help: bool,
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<&String>, expected: &str) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value.parse::<T>().map_err(|_| format!("invalid value '{}' for {}: expected {}", value, flag, expected))
+}
+
+/// Parses the command line arguments, not including the program name. Generation parameters are
+/// validated so that bad combinations are reported up front.
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        seed: None,
+        headless: false,
+        output: None,
+        params: GenParams::default(),
+        render_options: RenderOptions::default(),
+        heuristic: Heuristic::default(),
+        run_agents: false,
+        show_help: false,
+    };
+
+    // Asking for help shouldn't fail just because the other options don't make sense yet, so look
+    // for it before trying to parse any values.
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        options.show_help = true;
+        return Ok(options);
+    }
+
+    let mut i = 0;
+    while i < args.len() {
+        let flag = args[i].as_str();
+        let value = args.get(i + 1);
+
+        // Flags that take a value consume the next argument too.
+        let mut takes_value = true;
+        match flag {
+            "-r" | "--seed" => options.seed = Some(parse_value(flag, value, "a whole number")?),
+            "-g" | "--generator" => options.params.generator = parse_value(flag, value, "a generator name")?,
+            "--width" => options.params.width = parse_value(flag, value, "a whole number")?,
+            "--height" => options.params.height = parse_value(flag, value, "a whole number")?,
+            "--path-points" => options.params.path_point_count = parse_value(flag, value, "a whole number")?,
+            "--edge-chance" => options.params.edge_enabled_chance = parse_value(flag, value, "a number between 0 and 1")?,
+            "--erase-factor" => options.params.random_invalid_edge_erase_factor = parse_value(flag, value, "a whole number")?,
+            "--hybrid-fraction" => options.params.hybrid_switch_fraction = parse_value(flag, value, "a number between 0 and 1")?,
+            "--tree-policy" => options.params.growing_tree_policy = parse_value(flag, value, "newest, oldest, random, or a mix like newest:75,random:25")?,
+            "--bias" => options.params.bias = parse_value(flag, value, "ne, nw, se, or sw")?,
+            "--room-size" => options.params.room_size = parse_value(flag, value, "a whole number")?,
+            "--braid" => options.params.braid_fraction = parse_value(flag, value, "a number between 0 and 1")?,
+            "--sparseness" => options.params.sparseness = parse_value(flag, value, "a number between 0 and 1")?,
+            "--heuristic" => options.heuristic = parse_value(flag, value, "manhattan, euclidean, or zero")?,
+            "--output" => options.output = Some(parse_value(flag, value, "a file name")?),
+            "--cell-size" => options.render_options.scale_in_px = parse_value(flag, value, "a whole number")?,
+            "--wall-thickness" => options.render_options.edge_thickness_in_px = parse_value(flag, value, "a whole number")?,
+            "--margin" => options.render_options.draw_offset_in_px = parse_value(flag, value, "a whole number")?,
+            _ => {
+                takes_value = false;
+                match flag {
+                    "--headless" => options.headless = true,
+                    "--strict" => options.params.strict = true,
+                    "--longest-path" => options.params.longest_path = true,
+                    "--agents" => options.run_agents = true,
+                    "--no-path" => options.render_options.draw_path = false,
+                    _ => return Err(format!("unknown option '{}'", flag)),
+                }
+            },
+        }
+
+        i += if takes_value { 2 } else { 1 };
+    }
+
+    if options.render_options.scale_in_px == 0 {
+        return Err(String::from("--cell-size must be at least 1"));
+    }
+
+    options.params.validate().map_err(|e| e.to_string())?;
+
+    Ok(options)
+}
@@ -0,0 +1,185 @@
+//! Passes that change a maze after a generator has finished it.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::generators::{has_wall_between, maze_neighbors, open_entrance_and_exit, set_wall_between};
+use crate::grid::XY;
+use crate::maze::{CellGrid, EdgeState, GridCellKind, GridState};
+
+/// What the passes run on a finished maze did to it. Each field is `None` when that pass didn't run.
+#[derive(Clone, Debug, Default)]
+pub struct PostprocessReport {
+    /// How many cells [`cull`] removed.
+    pub cells_culled: Option<usize>,
+
+    /// How many walls [`braid`] removed.
+    pub walls_braided: Option<usize>,
+
+    /// What [`place_entrance_and_exit_far_apart`] returned: how many steps apart the entrance and
+    /// exit ended up, or `Some(None)` if there was nowhere to put them.
+    pub entrance_exit_distance: Option<Option<usize>>,
+}
+
+fn is_removed(grid: &CellGrid, point: &XY) -> bool {
+    matches!(grid[point].kind, GridCellKind::Removed)
+}
+
+fn is_dead_end(grid: &CellGrid, point: &XY) -> bool {
+    GridState::count_exits(grid, point) == 1
+}
+
+/// Every maze cell with only one way in or out.
+pub fn dead_ends(grid: &CellGrid) -> Vec<XY> {
+    let mut dead_ends = Vec::new();
+    for y in 0 .. grid.height() - 1 {
+        for x in 0 .. grid.width() - 1 {
+            let point = XY(x, y);
+            if is_dead_end(grid, &point) {
+                dead_ends.push(point);
+            }
+        }
+    }
+
+    dead_ends
+}
+
+/// Adds loops to a maze by knocking down a wall in dead ends until `fraction` (0 to 1) of the
+/// original dead ends are gone. A wall leading into another dead end is preferred, since removing
+/// it fixes both at once. Returns how many walls were removed.
+pub fn braid(rng: &mut StdRng, grid: &mut CellGrid, fraction: f64) -> usize {
+    let mut dead_ends = dead_ends(grid);
+    dead_ends.shuffle(rng);
+
+    let target = (dead_ends.len() as f64 * fraction).round() as usize;
+    let mut eliminated = 0;
+    let mut walls_removed = 0;
+
+    for point in dead_ends.iter() {
+        if eliminated >= target {
+            break;
+        }
+
+        // An earlier wall removal may have already opened this one up.
+        if !is_dead_end(grid, point) {
+            continue;
+        }
+
+        let walled: Vec<XY> = maze_neighbors(grid, point).into_iter()
+            .filter(|n| has_wall_between(grid, point, n) && !is_removed(grid, n))
+            .collect();
+        let walled_dead_ends: Vec<XY> = walled.iter().filter(|n| is_dead_end(grid, n)).cloned().collect();
+        let candidates = if walled_dead_ends.is_empty() { &walled } else { &walled_dead_ends };
+
+        // Only possible in a maze that is one cell wide.
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let neighbor = &candidates[rng.gen_range(0, candidates.len())];
+        eliminated += if is_dead_end(grid, neighbor) { 2 } else { 1 };
+        set_wall_between(grid, point, neighbor, EdgeState::Off);
+        walls_removed += 1;
+    }
+
+    walls_removed
+}
+
+/// Makes a maze sparser by filling in dead ends with solid rock until `fraction` (0 to 1) of the
+/// maze cells are gone. Filling in a dead end can turn the cell next to it into a new dead end, so
+/// long dead-end corridors get eaten away completely. Cells on the path are never removed. Returns
+/// how many cells were removed.
+pub fn cull(rng: &mut StdRng, grid: &mut CellGrid, fraction: f64) -> usize {
+    let cell_count = (grid.width() - 1) * (grid.height() - 1);
+    let target = (cell_count as f64 * fraction).round() as usize;
+    let mut removed = 0;
+
+    while removed < target {
+        let mut candidates: Vec<XY> = dead_ends(grid).into_iter().filter(|p| matches!(grid[p].kind, GridCellKind::Empty)).collect();
+        if candidates.is_empty() {
+            break;
+        }
+
+        candidates.shuffle(rng);
+        for point in candidates.iter() {
+            if removed >= target {
+                break;
+            }
+
+            // Filling in an earlier cell may have closed this one off completely.
+            if !is_dead_end(grid, point) {
+                continue;
+            }
+
+            let XY(x, y) = *point;
+            grid[point].left_edge = EdgeState::On;
+            grid[point].bottom_edge = EdgeState::On;
+            grid[XY(x + 1, y)].left_edge = EdgeState::On;
+            grid[XY(x, y + 1)].bottom_edge = EdgeState::On;
+            grid[point].kind = GridCellKind::Removed;
+            removed += 1;
+        }
+    }
+
+    removed
+}
+
+// The border cell furthest from `from`, and how far away it is.
+fn farthest_border_cell(grid: &CellGrid, border_cells: &[XY], from: &XY) -> Option<(XY, usize)> {
+    let distances = GridState::distances_from(grid, from);
+    border_cells.iter()
+        .filter_map(|point| distances[point].map(|distance| (point.clone(), distance)))
+        .max_by_key(|(_, distance)| *distance)
+}
+
+/// Moves the entrance and exit to the two border cells that are furthest apart, so that solving
+/// the maze takes as long as possible. The old openings are walled up and the path is marked again
+/// between the new ones. Returns how many steps apart they are, or `None` if there was nowhere to
+/// put them, in which case the maze is left as it was.
+///
+/// The two cells are found with a breadth-first search from any border cell to the border cell
+/// furthest from it, and then another from there. In a perfect maze, that lands on the pair of
+/// border cells with the longest route between them.
+pub fn place_entrance_and_exit_far_apart(rng: &mut StdRng, grid: &mut CellGrid) -> Option<usize> {
+    let width = grid.width();
+    let height = grid.height();
+
+    let mut border_cells = Vec::new();
+    for y in 0 .. height - 1 {
+        for x in 0 .. width - 1 {
+            let point = XY(x, y);
+            if GridState::is_valid_start_or_end(grid, &point) && !is_removed(grid, &point) {
+                border_cells.push(point);
+            }
+        }
+    }
+
+    // Find the new cells before touching anything, so the old entrance and exit stay put if there
+    // is nowhere better.
+    let (start, _) = farthest_border_cell(grid, &border_cells, border_cells.first()?)?;
+    let (end, distance) = farthest_border_cell(grid, &border_cells, &start)?;
+    if end == start {
+        return None;
+    }
+
+    // Close up the old entrance and exit, and forget the old path.
+    for x in 0 .. width - 1 {
+        grid[XY(x, 0)].bottom_edge = EdgeState::On;
+        grid[XY(x, height - 1)].bottom_edge = EdgeState::On;
+    }
+
+    for y in 0 .. height - 1 {
+        grid[XY(0, y)].left_edge = EdgeState::On;
+        grid[XY(width - 1, y)].left_edge = EdgeState::On;
+    }
+
+    for cell in grid.iter_mut() {
+        if let GridCellKind::Path(_) | GridCellKind::PathIntermediate | GridCellKind::End = cell.kind {
+            cell.kind = GridCellKind::Empty;
+        }
+    }
+
+    open_entrance_and_exit(rng, grid, &start, &end);
+    Some(distance)
+}
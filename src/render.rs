@@ -0,0 +1,356 @@
+use crate::generators::Highlight;
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, EdgeState, GridCellKind, GridState};
+
+/// An RGB color.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+    pub const BLACK: Color = Color { r: 0, g: 0, b: 0 };
+}
+
+/// The fill for cells that were culled into solid rock.
+const REMOVED_CELL_COLOR: Color = Color { r: 90, g: 90, b: 90 };
+
+/// Something that the maze can be drawn onto. Coordinates start at the bottom-left corner, with y
+/// increasing upwards, matching the maze grid.
+pub trait PixelSink {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn set_pixel(&mut self, x: usize, y: usize, color: &Color);
+}
+
+/// Sizes used when rasterizing a maze, all in pixels.
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    /// The size of one cell.
+    pub scale_in_px: usize,
+
+    /// How far in from the walls the fill color of a cell starts.
+    pub cell_fill_margin_in_px: usize,
+
+    /// The thickness of each wall.
+    pub edge_thickness_in_px: usize,
+
+    /// Blank space around the whole maze.
+    pub draw_offset_in_px: usize,
+
+    /// Whether to color in the cells on the generated path.
+    pub draw_path: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            scale_in_px: 25,
+            cell_fill_margin_in_px: 5,
+            edge_thickness_in_px: 3,
+            draw_offset_in_px: 20,
+            draw_path: true,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// The width and height of the image needed to hold the whole maze, including the offset on
+    /// every side.
+    pub fn image_size(&self, grid: &CellGrid) -> (usize, usize) {
+        let size = |cells: usize| {
+            ((cells - 1) * self.scale_in_px) + self.edge_thickness_in_px + (2 * self.draw_offset_in_px)
+        };
+
+        (size(grid.width()), size(grid.height()))
+    }
+
+    /// The maze cell under the given pixel, with y increasing upwards, if there is one.
+    pub fn cell_at(&self, grid: &CellGrid, x: usize, y: usize) -> Option<XY> {
+        let offset = self.draw_offset_in_px;
+        if x < offset || y < offset {
+            return None;
+        }
+
+        let point = XY((x - offset) / self.scale_in_px, (y - offset) / self.scale_in_px);
+        if point.0 < grid.width() - 1 && point.1 < grid.height() - 1 {
+            Some(point)
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// Draws walls and cells of a maze onto a [`PixelSink`].
+pub struct Renderer<'a> {
+    options: &'a RenderOptions,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(options: &'a RenderOptions) -> Renderer<'a> {
+        Renderer {
+            options,
+        }
+    }
+
+    fn draw_box(
+        &self,
+        sink: &mut dyn PixelSink,
+        x1: usize,
+        y1: usize,
+        x2: usize,
+        y2: usize,
+        color: &Color
+        ) {
+        let offset = self.options.draw_offset_in_px;
+        for draw_x in (x1 + offset) .. (x2 + offset).min(sink.width()) {
+            for draw_y in (y1 + offset) .. (y2 + offset).min(sink.height()) {
+                sink.set_pixel(draw_x, draw_y, color);
+            }
+        }
+    }
+
+    fn draw_vertical_edge(
+        &self,
+        sink: &mut dyn PixelSink,
+        x: usize,
+        y1: usize,
+        y2: usize,
+        color: &Color,
+        )
+    {
+        let scale = self.options.scale_in_px;
+        let thickness = self.options.edge_thickness_in_px;
+        self.draw_box(
+            sink,
+            x * scale,
+            y1 * scale,
+            (x * scale) + thickness,
+            (y2 * scale) + thickness,
+            color);
+    }
+
+    fn draw_horizontal_edge(
+        &self,
+        sink: &mut dyn PixelSink,
+        x1: usize,
+        x2: usize,
+        y: usize,
+        color: &Color,
+        )
+    {
+        let scale = self.options.scale_in_px;
+        let thickness = self.options.edge_thickness_in_px;
+        self.draw_box(
+            sink,
+            x1 * scale,
+            y * scale,
+            (x2 * scale) + thickness,
+            (y * scale) + thickness,
+            color);
+    }
+
+    /// Fills in the inside of a cell with the given color.
+    pub fn draw_cell(
+        &self,
+        sink: &mut dyn PixelSink,
+        x: usize,
+        y: usize,
+        color: &Color,
+        ) {
+        let scale = self.options.scale_in_px;
+        let margin = self.options.cell_fill_margin_in_px;
+        self.draw_box(
+            sink,
+            (x * scale) + margin,
+            (y * scale) + margin,
+            ((x+1) * scale).saturating_sub(margin),
+            ((y+1) * scale).saturating_sub(margin),
+            color,
+        );
+    }
+
+    /// Fills in the whole inside of a culled cell, right up to its walls.
+    fn draw_removed_cell(
+        &self,
+        sink: &mut dyn PixelSink,
+        x: usize,
+        y: usize,
+        )
+    {
+        let scale = self.options.scale_in_px;
+        let thickness = self.options.edge_thickness_in_px;
+        self.draw_box(
+            sink,
+            (x * scale) + thickness,
+            (y * scale) + thickness,
+            (x + 1) * scale,
+            (y + 1) * scale,
+            &REMOVED_CELL_COLOR,
+        );
+    }
+
+    /// The color a cell is filled with, based on its role in the maze.
+    pub fn cell_color(&self, grid: &CellGrid, path_len: usize, point: &XY) -> Color {
+        if self.options.draw_path {
+            match grid[point].kind {
+                GridCellKind::End => Color { r: 255, g: 50, b: 50 },
+                GridCellKind::Path(n) => Color { r: 50, g: 50, b: 255 - (((n as f32 / path_len as f32) * 255f32) as u8) },
+                GridCellKind::PathIntermediate => Color { r: 100, g: 100, b: 100 },
+                _ => Color::WHITE,
+            }
+        }
+        else {
+            Color::WHITE
+        }
+    }
+
+    /// Draws the maze with every reachable cell shaded by how far it is from wherever `distances`
+    /// was measured from. See [`distance_color`].
+    pub fn draw_distance_map(
+        &self,
+        sink: &mut dyn PixelSink,
+        grid: &CellGrid,
+        distances: &Grid<Option<usize>>,
+        )
+    {
+        let max_distance = distances.iter().filter_map(|distance| *distance).max().unwrap_or(0);
+        let overlay = |point: &XY| distances[point].map(|distance| distance_color(distance, max_distance));
+        self.draw_with_overlay(sink, grid, GridState::extract_path(grid).len(), &overlay);
+    }
+
+    /// Clears the sink and draws every wall and cell of the maze.
+    pub fn draw(
+        &self,
+        sink: &mut dyn PixelSink,
+        grid: &CellGrid,
+        path_len: usize,
+        )
+    {
+        self.draw_with_overlay(sink, grid, path_len, &|_| None);
+    }
+
+    /// Like [`Renderer::draw`], but any cell that `overlay` returns a color for is filled with
+    /// that color instead of its usual one.
+    pub fn draw_with_overlay(
+        &self,
+        sink: &mut dyn PixelSink,
+        grid: &CellGrid,
+        path_len: usize,
+        overlay: &dyn Fn(&XY) -> Option<Color>,
+        )
+    {
+        for y in 0 .. sink.height() {
+            for x in 0 .. sink.width() {
+                sink.set_pixel(x, y, &Color::WHITE);
+            }
+        }
+
+        for i in 0 .. grid.len() {
+            let XY(x, y) = grid.index_to_xy(i);
+            let cell = &grid[XY(x, y)];
+
+            // Draw left edge
+            if y < grid.height() - 1 {
+                match cell.left_edge {
+                    EdgeState::On => self.draw_vertical_edge(sink, x, y, y+1, &Color::BLACK),
+                    EdgeState::ProvisionallyOn => self.draw_vertical_edge(sink, x, y, y+1, &Color { r: 200, g: 200, b: 200 }),
+                    _ => ()
+                };
+            }
+
+            // Draw bottom edge
+            if x < grid.width() - 1 {
+                match cell.bottom_edge {
+                    EdgeState::On => self.draw_horizontal_edge(sink, x, x+1, y, &Color::BLACK),
+                    EdgeState::ProvisionallyOn => self.draw_horizontal_edge(sink, x, x+1, y, &Color { r: 200, g: 200, b: 200 }),
+                    _ => ()
+                };
+            }
+
+            // The last row and column only hold border walls.
+            if x < grid.width() - 1 && y < grid.height() - 1 {
+                let point = XY(x, y);
+                if let GridCellKind::Removed = cell.kind {
+                    self.draw_removed_cell(sink, x, y);
+                    continue;
+                }
+
+                let color = overlay(&point).unwrap_or_else(|| self.cell_color(grid, path_len, &point));
+                self.draw_cell(sink, x, y, &color);
+            }
+        }
+    }
+}
+
+/// The color used to show a cell that a generator is highlighting.
+pub fn highlight_color(highlight: Highlight) -> Color {
+    match highlight {
+        Highlight::Current => Color { r: 255, g: 160, b: 0 },
+        Highlight::Frontier => Color { r: 255, g: 230, b: 140 },
+        Highlight::Walk => Color { r: 120, g: 200, b: 120 },
+        Highlight::Scan => Color { r: 170, g: 140, b: 230 },
+    }
+}
+
+/// The color for a cell that is `distance` steps away, on a gradient from blue for the closest
+/// cells to red for cells `max_distance` away.
+pub fn distance_color(distance: usize, max_distance: usize) -> Color {
+    let fraction = if max_distance == 0 { 0.0 } else { distance as f32 / max_distance as f32 };
+    Color { r: (fraction * 255f32) as u8, g: 50, b: 255 - ((fraction * 255f32) as u8) }
+}
+
+/// The color used to show the solved route through a finished maze.
+pub const SOLUTION_COLOR: Color = Color { r: 60, g: 200, b: 90 };
+
+/// An in-memory RGB image, stored top row first.
+pub struct RgbImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl RgbImage {
+    pub fn new(width: usize, height: usize) -> RgbImage {
+        RgbImage {
+            width,
+            height,
+            pixels: vec![255; width * height * 3],
+        }
+    }
+
+    /// The raw RGB bytes, three per pixel, top row first.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+}
+
+impl PixelSink for RgbImage {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, color: &Color) {
+        // Sink coordinates have y going up, but the image is stored top row first.
+        let index = (((self.height - 1 - y) * self.width) + x) * 3;
+        self.pixels[index] = color.r;
+        self.pixels[index + 1] = color.g;
+        self.pixels[index + 2] = color.b;
+    }
+}
+
+/// Rasterizes a maze into a new image sized to fit it.
+pub fn render_to_image(grid: &CellGrid, options: &RenderOptions) -> RgbImage {
+    let (width, height) = options.image_size(grid);
+    let mut image = RgbImage::new(width, height);
+    Renderer::new(options).draw(&mut image, grid, GridState::extract_path(grid).len());
+    image
+}
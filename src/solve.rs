@@ -0,0 +1,316 @@
+//! Solvers that find routes through a maze once it has been generated.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::generators::{has_wall_between, maze_neighbors};
+use crate::grid::{Grid, XY};
+use crate::maze::{CellGrid, GridCellKind};
+
+/// The cells the generator marked as the start and end of the path, if it marked both.
+pub fn find_entrance_and_exit(grid: &CellGrid) -> Option<(XY, XY)> {
+    let (start, end) = find_start_and_end(grid);
+    Some((start?, end?))
+}
+
+/// The cells the generator marked as the start and end of the path, each `None` if it wasn't
+/// marked.
+pub fn find_start_and_end(grid: &CellGrid) -> (Option<XY>, Option<XY>) {
+    let mut start = None;
+    let mut end = None;
+    for (i, cell) in grid.iter().enumerate() {
+        match cell.kind {
+            GridCellKind::Path(0) => start = Some(grid.index_to_xy(i)),
+            GridCellKind::End => end = Some(grid.index_to_xy(i)),
+            _ => (),
+        }
+    }
+
+    (start, end)
+}
+
+/// Finds the shortest route between two cells by walking through open walls, breadth first.
+/// Returns the cells from `start` to `end`, or `None` if they aren't connected.
+pub fn shortest_path(grid: &CellGrid, start: &XY, end: &XY) -> Option<Vec<XY>> {
+    let mut came_from: Grid<Option<XY>> = Grid::new(grid.width(), grid.height(), &None);
+    let mut queue = VecDeque::new();
+    came_from[start] = Some(start.clone());
+    queue.push_back(start.clone());
+
+    while let Some(point) = queue.pop_front() {
+        if point == *end {
+            let mut route = vec![point];
+            while route.last().unwrap() != start {
+                let previous = came_from[route.last().unwrap()].clone().unwrap();
+                route.push(previous);
+            }
+
+            route.reverse();
+            return Some(route);
+        }
+
+        for neighbor in maze_neighbors(grid, &point) {
+            if came_from[&neighbor].is_none() && !has_wall_between(grid, &point, &neighbor) {
+                came_from[&neighbor] = Some(point.clone());
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    None
+}
+
+/// The shortest route from the entrance of the maze to its exit. This can be shorter than the
+/// path the generator marked, if there are shortcuts that the generator didn't know about.
+pub fn solve(grid: &CellGrid) -> Option<Vec<XY>> {
+    let (start, end) = find_entrance_and_exit(grid)?;
+    shortest_path(grid, &start, &end)
+}
+
+/// The cell furthest from wherever a distance map was measured from, and how far away it is.
+pub fn farthest_cell(distances: &Grid<Option<usize>>) -> Option<(XY, usize)> {
+    distances.iter().enumerate()
+        .filter_map(|(i, distance)| distance.map(|distance| (distances.index_to_xy(i), distance)))
+        .max_by_key(|(_, distance)| *distance)
+}
+
+/// The estimate A* uses for how much further it is from a cell to the end.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Heuristic {
+    /// Steps along the grid, ignoring walls.
+    #[default]
+    Manhattan,
+
+    /// The straight-line distance.
+    Euclidean,
+
+    /// No estimate at all, which makes A* behave like Dijkstra's algorithm.
+    Zero,
+}
+
+impl Heuristic {
+    // Scaled by the cheapest cell so that the estimate never overshoots the real cost.
+    fn estimate(self, from: &XY, to: &XY, min_cost: u32) -> f64 {
+        let dx = (from.0 as f64 - to.0 as f64).abs();
+        let dy = (from.1 as f64 - to.1 as f64).abs();
+        let distance = match self {
+            Heuristic::Manhattan => dx + dy,
+            Heuristic::Euclidean => ((dx * dx) + (dy * dy)).sqrt(),
+            Heuristic::Zero => 0.0,
+        };
+
+        distance * min_cost as f64
+    }
+}
+
+impl FromStr for Heuristic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "manhattan" => Ok(Heuristic::Manhattan),
+            "euclidean" => Ok(Heuristic::Euclidean),
+            "zero" => Ok(Heuristic::Zero),
+            _ => Err(format!("unknown heuristic '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Heuristic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Heuristic::Manhattan => "manhattan",
+            Heuristic::Euclidean => "euclidean",
+            Heuristic::Zero => "zero",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Where a cell is in an A* search.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeState {
+    Unseen,
+
+    /// Found, but not expanded yet.
+    Open,
+
+    /// Expanded, with its cheapest cost known.
+    Closed,
+}
+
+/// What A* found.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AStarResult {
+    /// The cells from start to end.
+    pub path: Vec<XY>,
+
+    /// The sum of the costs of every cell entered along the path, not counting the start.
+    pub cost: u32,
+
+    /// How many cells were expanded before the end was reached.
+    pub expanded: usize,
+}
+
+/// The result of one step of an [`AStar`] search.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchStatus {
+    Searching,
+    Found(AStarResult),
+    NotFound,
+}
+
+// An entry in the open set. The heap is a max-heap, so the ordering is reversed to pop the lowest
+// estimated total first.
+struct OpenEntry {
+    estimate: f64,
+    point: XY,
+}
+
+impl PartialEq for OpenEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for OpenEntry {}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+/// Every cell costs the same to enter.
+pub fn uniform_costs(grid: &CellGrid) -> Grid<u32> {
+    Grid::new(grid.width(), grid.height(), &1)
+}
+
+/// An A* search that can be run one expansion at a time, so that it can be animated.
+pub struct AStar {
+    end: XY,
+    heuristic: Heuristic,
+
+    // What it costs to move into each cell.
+    costs: Grid<u32>,
+    min_cost: u32,
+
+    open: BinaryHeap<OpenEntry>,
+    best_cost: Grid<Option<u32>>,
+    came_from: Grid<Option<XY>>,
+    states: Grid<NodeState>,
+    expanded: usize,
+    status: SearchStatus,
+}
+
+impl AStar {
+    /// Prepares a search from `start` to `end`. `costs` must be the same size as the grid.
+    pub fn new(grid: &CellGrid, start: &XY, end: &XY, heuristic: Heuristic, costs: Grid<u32>) -> AStar {
+        let min_cost = costs.iter().cloned().min().unwrap_or(1);
+        let mut search = AStar {
+            end: end.clone(),
+            heuristic,
+            costs,
+            min_cost,
+            open: BinaryHeap::new(),
+            best_cost: Grid::new(grid.width(), grid.height(), &None),
+            came_from: Grid::new(grid.width(), grid.height(), &None),
+            states: Grid::new(grid.width(), grid.height(), &NodeState::Unseen),
+            expanded: 0,
+            status: SearchStatus::Searching,
+        };
+
+        search.best_cost[start] = Some(0);
+        search.states[start] = NodeState::Open;
+        search.open.push(OpenEntry { estimate: heuristic.estimate(start, end, min_cost), point: start.clone() });
+        search
+    }
+
+    /// Expands the most promising open cell.
+    pub fn step(&mut self, grid: &CellGrid) -> &SearchStatus {
+        if self.status != SearchStatus::Searching {
+            return &self.status;
+        }
+
+        // Cells can be in the heap more than once if a cheaper route to them turned up, so skip
+        // the ones that were already expanded.
+        let point = loop {
+            match self.open.pop() {
+                Some(entry) if self.states[&entry.point] == NodeState::Closed => continue,
+                Some(entry) => break entry.point,
+                None => {
+                    self.status = SearchStatus::NotFound;
+                    return &self.status;
+                },
+            }
+        };
+
+        self.states[&point] = NodeState::Closed;
+        self.expanded += 1;
+
+        let cost = self.best_cost[&point].unwrap();
+        if point == self.end {
+            let mut path = vec![point];
+            while let Some(previous) = self.came_from[path.last().unwrap()].clone() {
+                path.push(previous);
+            }
+
+            path.reverse();
+            self.status = SearchStatus::Found(AStarResult { path, cost, expanded: self.expanded });
+            return &self.status;
+        }
+
+        for neighbor in maze_neighbors(grid, &point) {
+            if self.states[&neighbor] == NodeState::Closed || has_wall_between(grid, &point, &neighbor) {
+                continue;
+            }
+
+            // Saturate rather than wrap, so that a huge cost can't come out looking cheap.
+            let neighbor_cost = cost.saturating_add(self.costs[&neighbor]);
+            if self.best_cost[&neighbor].is_none_or(|best| neighbor_cost < best) {
+                self.best_cost[&neighbor] = Some(neighbor_cost);
+                self.came_from[&neighbor] = Some(point.clone());
+                self.states[&neighbor] = NodeState::Open;
+                let estimate = neighbor_cost as f64 + self.heuristic.estimate(&neighbor, &self.end, self.min_cost);
+                self.open.push(OpenEntry { estimate, point: neighbor });
+            }
+        }
+
+        &self.status
+    }
+
+    /// Runs the search until the end is found or every reachable cell has been expanded.
+    pub fn run(mut self, grid: &CellGrid) -> Option<AStarResult> {
+        while self.step(grid) == &SearchStatus::Searching {
+        }
+
+        match self.status {
+            SearchStatus::Found(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Where the given cell is in the search.
+    pub fn node_state(&self, point: &XY) -> NodeState {
+        self.states[point]
+    }
+
+    pub fn status(&self) -> &SearchStatus {
+        &self.status
+    }
+}
+
+/// Finds the cheapest route from `start` to `end` with A*, where entering each cell costs the
+/// amount given in `costs`.
+pub fn astar(grid: &CellGrid, start: &XY, end: &XY, heuristic: Heuristic, costs: Grid<u32>) -> Option<AStarResult> {
+    AStar::new(grid, start, end, heuristic, costs).run(grid)
+}
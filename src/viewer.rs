@@ -0,0 +1,365 @@
+//! The windowed viewer, which animates generation and lets the finished maze be explored.
+
+use pixel_canvas::{
+    Canvas,
+    canvas::CanvasInfo,
+    Color,
+    image::Image,
+    input::{
+        Event,
+        MouseState,
+        WindowEvent,
+        glutin::event::{
+            KeyboardInput,
+            ElementState,
+            MouseButton,
+            VirtualKeyCode,
+        },
+    },
+};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use mazes::{
+    agents::{self, AgentStatus, SolverAgent, AGENT_NAMES},
+    generators::{GENERATOR_NAMES, Highlight},
+    grid::{Grid, XY},
+    maze::GridState,
+    render::{
+        self,
+        PixelSink,
+        RenderOptions,
+        Renderer,
+    },
+    solve::{
+        self,
+        AStar,
+        Heuristic,
+        NodeState,
+        SearchStatus,
+    },
+};
+
+use crate::print_generation_report;
+
+/// Lets the library renderer draw into the window's image.
+struct ImageSink<'a>(&'a mut Image);
+
+impl<'a> PixelSink for ImageSink<'a> {
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, color: &render::Color) {
+        self.0[pixel_canvas::XY(x, y)] = Color { r: color.r, g: color.g, b: color.b };
+    }
+}
+
+#[derive(Clone)]
+enum Command {
+    Exit,
+    Refresh,
+    NextGenerator,
+    StartSearch,
+    ToggleDistances,
+    PickDistanceOrigin,
+    NextAgent,
+}
+
+/// The windowed viewer. Owns the maze generator and steps it once per frame while drawing it.
+struct Viewer {
+    maze: GridState,
+    mouse_state: MouseState,
+    next_command: Option<Command>,
+    render_options: RenderOptions,
+    show_solution: bool,
+
+    // An A* search being animated over the finished maze, one expansion per frame.
+    heuristic: Heuristic,
+    search: Option<AStar>,
+
+    // A step-wise solving agent being animated over the finished maze, one step per frame.
+    agent: Option<Box<dyn SolverAgent>>,
+    agent_rng: StdRng,
+
+    // When set, the finished maze is shaded by distance from this cell.
+    distance_origin: Option<XY>,
+}
+
+impl Viewer {
+    fn new(maze: GridState, render_options: RenderOptions, heuristic: Heuristic, seed: u64) -> Viewer {
+        Viewer {
+            maze,
+            mouse_state: MouseState::new(),
+            next_command: None,
+            render_options,
+            show_solution: false,
+            heuristic,
+            search: None,
+            agent: None,
+            agent_rng: StdRng::seed_from_u64(seed),
+            distance_origin: None,
+        }
+    }
+
+    fn print_farthest_cell(&self) {
+        if let Some(ref origin) = self.distance_origin {
+            let distances = GridState::distances_from(self.maze.grid(), origin);
+            if let Some((XY(x, y), distance)) = solve::farthest_cell(&distances) {
+                println!("Farthest cell from ({}, {}) is ({}, {}), {} steps away", origin.0, origin.1, x, y, distance);
+            }
+        }
+    }
+
+    fn update_search(&mut self) {
+        if let Some(ref mut search) = self.search {
+            if search.status() != &SearchStatus::Searching {
+                return;
+            }
+
+            match search.step(self.maze.grid()) {
+                SearchStatus::Searching => (),
+                SearchStatus::Found(result) => {
+                    println!("A* ({}) found a route of {} cells costing {} after expanding {} cells",
+                        self.heuristic, result.path.len(), result.cost, result.expanded);
+                },
+                SearchStatus::NotFound => println!("A* ({}) found no route", self.heuristic),
+            }
+        }
+    }
+
+    fn update_agent(&mut self) {
+        if let Some(ref mut agent) = self.agent {
+            if agent.status() != AgentStatus::Running {
+                return;
+            }
+
+            let status = agent.step(&mut self.agent_rng, self.maze.grid());
+            if status != AgentStatus::Running {
+                println!("{}: {} after {} steps", agent.name(), status, agent.steps());
+            }
+        }
+    }
+
+    fn process_command(&mut self) {
+        match self.next_command {
+            Some(Command::Exit) => std::process::exit(0),
+            Some(Command::Refresh) => {
+                self.search = None;
+                self.agent = None;
+                self.distance_origin = None;
+                self.maze.start_generate_maze();
+            },
+            Some(Command::NextGenerator) => {
+                self.search = None;
+                self.agent = None;
+                self.distance_origin = None;
+
+                // Skip over any generator that can't work with the current parameters.
+                let current = GENERATOR_NAMES.iter().position(|&name| name == self.maze.generator().name()).unwrap_or(0);
+                for offset in 1 .. GENERATOR_NAMES.len() {
+                    let next = GENERATOR_NAMES[(current + offset) % GENERATOR_NAMES.len()];
+                    match self.maze.set_generator(next) {
+                        Ok(()) => {
+                            println!("Switched to generator {}", next);
+                            break;
+                        },
+                        Err(e) => println!("Can't switch to {}: {}", next, e),
+                    }
+                }
+            },
+            Some(Command::StartSearch) => {
+                self.agent = None;
+                let grid = self.maze.grid();
+                self.search = match solve::find_entrance_and_exit(grid) {
+                    Some((start, end)) if self.maze.is_done() => {
+                        Some(AStar::new(grid, &start, &end, self.heuristic, solve::uniform_costs(grid)))
+                    },
+                    _ => None,
+                };
+            },
+            Some(Command::ToggleDistances) => {
+                self.distance_origin = match self.distance_origin {
+                    Some(_) => None,
+                    None => Some(solve::find_entrance_and_exit(self.maze.grid()).map_or(XY(0, 0), |(start, _)| start)),
+                };
+                self.print_farthest_cell();
+            },
+            Some(Command::PickDistanceOrigin) => {
+                let (x, y) = (self.mouse_state.x.max(0) as usize, self.mouse_state.y.max(0) as usize);
+                if let Some(point) = self.render_options.cell_at(self.maze.grid(), x, y) {
+                    self.distance_origin = Some(point);
+                    self.print_farthest_cell();
+                }
+            },
+            Some(Command::NextAgent) if self.maze.is_done() => {
+                self.search = None;
+
+                // Each press starts the agent after the one that was last running.
+                let next = match self.agent {
+                    Some(ref agent) => {
+                        let current = AGENT_NAMES.iter().position(|&name| name == agent.name()).unwrap_or(0);
+                        AGENT_NAMES[(current + 1) % AGENT_NAMES.len()]
+                    },
+                    None => AGENT_NAMES[0],
+                };
+
+                self.agent = agents::create_agent(next, self.maze.grid());
+                if self.agent.is_some() {
+                    println!("Started agent {}", next);
+                }
+            },
+            _ => (),
+        };
+
+        self.next_command = None;
+    }
+
+    fn handle_input(
+        info: &CanvasInfo,
+        state: &mut Viewer,
+        event: &Event<()>
+        ) -> bool {
+        let handled_mouse = MouseState::handle_input(info, &mut state.mouse_state, event);
+
+        let handled_key = if state.next_command.is_none() {
+            match event {
+                Event::WindowEvent {
+                    event: WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Released,
+                            virtual_keycode: Some(vk),
+                            ..
+                        },
+                        ..
+                    },
+                    ..
+                } => {
+                    state.next_command = match vk {
+                        VirtualKeyCode::Escape => Some(Command::Exit),
+                        VirtualKeyCode::F5 => Some(Command::Refresh),
+                        VirtualKeyCode::G => Some(Command::NextGenerator),
+                        VirtualKeyCode::A => Some(Command::StartSearch),
+                        VirtualKeyCode::D => Some(Command::ToggleDistances),
+                        VirtualKeyCode::W => Some(Command::NextAgent),
+                        VirtualKeyCode::P => {
+                            state.render_options.draw_path = !state.render_options.draw_path;
+                            None
+                        },
+                        VirtualKeyCode::S => {
+                            state.show_solution = !state.show_solution;
+                            None
+                        },
+                        _ => None
+                    };
+
+                    state.next_command.is_some()
+                },
+                Event::WindowEvent {
+                    event: WindowEvent::MouseInput {
+                        state: ElementState::Released,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                    ..
+                } if state.maze.is_done() => {
+                    state.next_command = Some(Command::PickDistanceOrigin);
+                    true
+                },
+                _ => false,
+            }
+        } else {
+            false
+        };
+
+        handled_mouse || handled_key
+    }
+
+    fn draw(
+        &self,
+        image: &mut Image,
+        )
+    {
+        if let Some(ref origin) = self.distance_origin {
+            if self.maze.is_done() {
+                let distances = GridState::distances_from(self.maze.grid(), origin);
+                Renderer::new(&self.render_options).draw_distance_map(&mut ImageSink(image), self.maze.grid(), &distances);
+                return;
+            }
+        }
+
+        // Show what the generator is working on until the maze is done, then the solution if it
+        // was asked for.
+        let generator = self.maze.generator();
+        let is_done = self.maze.is_done();
+        let grid = self.maze.grid();
+        let mut on_solution = Grid::new(grid.width(), grid.height(), &false);
+        if self.show_solution {
+            for point in self.maze.solution().unwrap_or(&[]) {
+                on_solution[point] = true;
+            }
+        }
+
+        let mut on_search_path = Grid::new(grid.width(), grid.height(), &false);
+        if let Some(SearchStatus::Found(result)) = self.search.as_ref().map(AStar::status) {
+            for point in result.path.iter() {
+                on_search_path[point] = true;
+            }
+        }
+
+        let search = self.search.as_ref();
+        let agent = self.agent.as_ref();
+        let overlay = |point: &XY| {
+            if !is_done {
+                generator.highlight(point).map(render::highlight_color)
+            }
+            else if let Some(agent) = agent {
+                agent.highlight(point).map(render::highlight_color)
+            }
+            else if on_search_path[point] {
+                Some(render::SOLUTION_COLOR)
+            }
+            else if let Some(search) = search {
+                match search.node_state(point) {
+                    NodeState::Open => Some(render::highlight_color(Highlight::Frontier)),
+                    NodeState::Closed => Some(render::highlight_color(Highlight::Scan)),
+                    NodeState::Unseen => None,
+                }
+            }
+            else if on_solution[point] {
+                Some(render::SOLUTION_COLOR)
+            }
+            else {
+                None
+            }
+        };
+
+        Renderer::new(&self.render_options).draw_with_overlay(&mut ImageSink(image), self.maze.grid(), self.maze.path().len(), &overlay);
+    }
+}
+
+/// Opens a window sized to fit the maze and animates it until the window is closed.
+pub fn run(grid_state: GridState, render_options: RenderOptions, heuristic: Heuristic, seed: u64) {
+    let (window_width, window_height) = render_options.image_size(grid_state.grid());
+    let canvas = Canvas::new(window_width, window_height)
+        .title("Mazes")
+        .state(Viewer::new(grid_state, render_options, heuristic, seed))
+        .input(Viewer::handle_input)
+        ;
+
+    canvas.render(|viewer, image| {
+        viewer.process_command();
+        if viewer.maze.update() {
+            print_generation_report(&viewer.maze);
+        }
+
+        viewer.update_search();
+        viewer.update_agent();
+        viewer.draw(image);
+    });
+}
@@ -0,0 +1,74 @@
+extern crate mazes;
+
+use mazes::generators::GENERATOR_NAMES;
+use mazes::maze::{GenParams, GridState};
+
+const SEEDS: &[u64] = &[0, 1, 2, 7, 42];
+const SIZES: &[(usize, usize)] = &[(3, 3), (3, 8), (8, 3), (10, 10), (21, 16)];
+
+fn generate(seed: u64, params: GenParams) -> GridState {
+    let mut maze = GridState::new(seed, params, false).unwrap();
+    maze.generate_to_completion();
+    maze
+}
+
+#[test]
+fn perfect_generators_make_perfect_mazes() {
+    // Everything except path-fill is supposed to come out perfect without any help.
+    for name in GENERATOR_NAMES.iter().filter(|name| **name != "path-fill") {
+        for &(width, height) in SIZES {
+            for &seed in SEEDS {
+                let params = GenParams {
+                    generator: String::from(*name),
+                    width,
+                    height,
+                    ..GenParams::default()
+                };
+
+                let maze = generate(seed, params);
+                let report = GridState::check_maze(maze.grid());
+                assert!(report.is_clean(), "{} {}x{} seed {}: {}", name, width, height, seed, report);
+            }
+        }
+    }
+}
+
+#[test]
+fn strict_path_fill_ends_clean() {
+    for &(width, height) in &[(10, 10), (20, 20)] {
+        for &seed in SEEDS {
+            let params = GenParams {
+                width,
+                height,
+                path_point_count: 4,
+                strict: true,
+                ..GenParams::default()
+            };
+
+            let maze = generate(seed, params);
+            let report = GridState::check_maze(maze.grid());
+            assert!(report.is_clean(), "{}x{} seed {}: {}", width, height, seed, report);
+        }
+    }
+}
+
+#[test]
+fn path_fill_finishes_with_low_edge_chance_or_large_grid() {
+    // Neither of these ever rolls walls that leave every 2x2 block walled in, so they used to
+    // keep re-rolling forever.
+    let low_chance = GenParams {
+        edge_enabled_chance: 0.1,
+        ..GenParams::default()
+    };
+
+    let large_grid = GenParams {
+        width: 40,
+        height: 40,
+        ..GenParams::default()
+    };
+
+    for params in [low_chance, large_grid] {
+        let maze = generate(1, params);
+        assert!(maze.is_done());
+    }
+}
@@ -0,0 +1,120 @@
+extern crate mazes;
+
+use mazes::generators::{Bias, CellPick, GrowingTreePolicy};
+use mazes::maze::{GenParams, ParamError};
+use mazes::solve::Heuristic;
+
+#[test]
+fn default_params_are_valid() {
+    assert_eq!(GenParams::default().validate(), Ok(()));
+}
+
+#[test]
+fn validate_grid_size() {
+    let smallest = GenParams { width: 3, height: 3, path_point_count: 2, ..GenParams::default() };
+    assert_eq!(smallest.validate(), Ok(()));
+
+    let narrow = GenParams { width: 2, height: 3, ..smallest.clone() };
+    assert_eq!(narrow.validate(), Err(ParamError::GridTooSmall { width: 2, height: 3 }));
+
+    let short = GenParams { width: 3, height: 2, ..smallest };
+    assert_eq!(short.validate(), Err(ParamError::GridTooSmall { width: 3, height: 2 }));
+}
+
+#[test]
+fn validate_path_points() {
+    let params = GenParams { width: 5, height: 8, ..GenParams::default() };
+    let max = params.max_path_point_count();
+    assert_eq!(max, 7);
+
+    assert_eq!(GenParams { path_point_count: 2, ..params.clone() }.validate(), Ok(()));
+    assert_eq!(GenParams { path_point_count: max, ..params.clone() }.validate(), Ok(()));
+    assert_eq!(GenParams { path_point_count: 1, ..params.clone() }.validate(), Err(ParamError::TooFewPathPoints(1)));
+    assert_eq!(
+        GenParams { path_point_count: max + 1, ..params.clone() }.validate(),
+        Err(ParamError::TooManyPathPoints { requested: max + 1, max }));
+
+    // Only path-fill has path points to worry about.
+    let backtracker = GenParams { generator: String::from("backtracker"), path_point_count: max + 1, ..params };
+    assert_eq!(backtracker.validate(), Ok(()));
+}
+
+#[test]
+fn validate_fractions() {
+    let params = GenParams::default();
+
+    assert_eq!(GenParams { edge_enabled_chance: 1.0, ..params.clone() }.validate(), Ok(()));
+    assert_eq!(GenParams { edge_enabled_chance: 0.0, ..params.clone() }.validate(), Err(ParamError::EdgeChanceOutOfRange(0.0)));
+    assert_eq!(GenParams { edge_enabled_chance: 1.5, ..params.clone() }.validate(), Err(ParamError::EdgeChanceOutOfRange(1.5)));
+    assert!(GenParams { edge_enabled_chance: f64::NAN, ..params.clone() }.validate().is_err());
+
+    for &fraction in &[0.0, 1.0] {
+        assert_eq!(GenParams { hybrid_switch_fraction: fraction, ..params.clone() }.validate(), Ok(()));
+        assert_eq!(GenParams { braid_fraction: fraction, ..params.clone() }.validate(), Ok(()));
+        assert_eq!(GenParams { sparseness: fraction, ..params.clone() }.validate(), Ok(()));
+    }
+
+    assert_eq!(
+        GenParams { hybrid_switch_fraction: -0.1, ..params.clone() }.validate(),
+        Err(ParamError::HybridFractionOutOfRange(-0.1)));
+    assert_eq!(
+        GenParams { braid_fraction: 1.1, ..params.clone() }.validate(),
+        Err(ParamError::BraidFractionOutOfRange(1.1)));
+    assert_eq!(
+        GenParams { sparseness: 1.1, ..params }.validate(),
+        Err(ParamError::SparsenessOutOfRange(1.1)));
+}
+
+#[test]
+fn validate_other_params() {
+    let params = GenParams::default();
+
+    assert_eq!(GenParams { random_invalid_edge_erase_factor: 0, ..params.clone() }.validate(), Err(ParamError::EraseFactorZero));
+    assert_eq!(GenParams { room_size: 0, ..params.clone() }.validate(), Err(ParamError::RoomSizeZero));
+    assert_eq!(
+        GenParams { generator: String::from("nope"), ..params }.validate(),
+        Err(ParamError::UnknownGenerator(String::from("nope"))));
+}
+
+#[test]
+fn parse_growing_tree_policy() {
+    assert_eq!("newest".parse(), Ok(GrowingTreePolicy::single(CellPick::Newest)));
+    assert_eq!("oldest".parse(), Ok(GrowingTreePolicy::single(CellPick::Oldest)));
+    assert_eq!(
+        "newest:75,random:25".parse(),
+        Ok(GrowingTreePolicy::weighted(&[(CellPick::Newest, 75), (CellPick::Random, 25)]).unwrap()));
+    assert_eq!("newest:75,random:25".parse::<GrowingTreePolicy>().unwrap().to_string(), "newest:75,random:25");
+
+    // Zero weights are dropped, but something has to be left.
+    assert_eq!("oldest:0,random:1".parse(), Ok(GrowingTreePolicy::weighted(&[(CellPick::Random, 1)]).unwrap()));
+    assert!("newest:0".parse::<GrowingTreePolicy>().is_err());
+
+    assert!("".parse::<GrowingTreePolicy>().is_err());
+    assert!("widest".parse::<GrowingTreePolicy>().is_err());
+    assert!("newest:lots".parse::<GrowingTreePolicy>().is_err());
+    assert!("newest:-1".parse::<GrowingTreePolicy>().is_err());
+    assert!(format!("newest:{},random:1", u32::MAX).parse::<GrowingTreePolicy>().is_err());
+}
+
+#[test]
+fn parse_bias() {
+    for bias in &[Bias::NorthEast, Bias::NorthWest, Bias::SouthEast, Bias::SouthWest] {
+        assert_eq!(bias.to_string().parse(), Ok(*bias));
+    }
+
+    assert_eq!("ne".parse(), Ok(Bias::NorthEast));
+    assert!("NE".parse::<Bias>().is_err());
+    assert!("north".parse::<Bias>().is_err());
+}
+
+#[test]
+fn parse_heuristic() {
+    assert_eq!("manhattan".parse(), Ok(Heuristic::Manhattan));
+    assert_eq!("euclidean".parse(), Ok(Heuristic::Euclidean));
+    assert_eq!("zero".parse(), Ok(Heuristic::Zero));
+    assert!("chebyshev".parse::<Heuristic>().is_err());
+
+    for heuristic in &[Heuristic::Manhattan, Heuristic::Euclidean, Heuristic::Zero] {
+        assert_eq!(heuristic.to_string().parse(), Ok(*heuristic));
+    }
+}